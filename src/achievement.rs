@@ -1,15 +1,18 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::{Duration, SystemTime},
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::setting::Setting;
+use crate::{language, rarity, schema, setting::Setting, validate::Problem};
 type Name = String;
 
+/// extensions tried for an icon file without one
+const ICON_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "bmp", "ico"];
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Achievement {
     pub earned: bool,
@@ -36,8 +39,24 @@ pub struct AchievementRaw {
 #[derive(Clone, Debug, Default)]
 pub struct AchievementsRaw {
     pub achievements: Vec<AchievementRaw>,
-    pub image_dir: PathBuf,
+    /// directories of the icon files, in search order
+    pub image_dirs: Vec<PathBuf>,
     pub languages: Vec<String>,
+    /// global unlock percents by achievement name
+    pub rarity: HashMap<Name, f64>,
+}
+
+/// What happened to an achievement, decides the popup sound
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    #[default]
+    Unlock,
+    Relock,
+    RareUnlock,
+    /// the last remaining achievement is earned
+    Completion,
+    /// earned count crosses 25%, 50% or 75%
+    Progress,
 }
 
 impl Achievement {
@@ -50,6 +69,43 @@ impl Achievement {
         datetime.format("%Y-%m-%d %T").to_string()
     }
 }
+impl EventKind {
+    pub const ALL: [EventKind; 5] = [
+        EventKind::Unlock,
+        EventKind::Relock,
+        EventKind::RareUnlock,
+        EventKind::Completion,
+        EventKind::Progress,
+    ];
+
+    /// name used in the setting and for sound pack files
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Unlock => "unlock",
+            EventKind::Relock => "relock",
+            EventKind::RareUnlock => "rare_unlock",
+            EventKind::Completion => "completion",
+            EventKind::Progress => "progress",
+        }
+    }
+
+    /// kind of an unlock that brings the earned count to `earned` out of `total`,
+    /// rarity being the global unlock percent of the achievement
+    pub fn for_unlock(earned: usize, total: usize, rarity: Option<f64>) -> Self {
+        if total == 0 || earned < 1 {
+            EventKind::Unlock
+        } else if earned >= total {
+            EventKind::Completion
+        } else if rarity.is_some_and(|r| rarity::Tier::of(r) == rarity::Tier::Rare) {
+            EventKind::RareUnlock
+        } else if earned * 4 / total != (earned - 1) * 4 / total {
+            EventKind::Progress
+        } else {
+            EventKind::Unlock
+        }
+    }
+}
+
 impl Achievements {
     /// Create a new Achievements from the path
     pub fn new(setting: &Setting) -> Self {
@@ -85,15 +141,31 @@ impl Achievements {
 }
 
 impl AchievementsRaw {
-    /// read achievements from path(./steam_settings/achievements.json)
-    pub fn new(setting: &Setting) -> Self {
-        let achievements = fs::read_to_string(setting.get_achievement_json_path()).unwrap();
-        let achievements: Vec<AchievementRaw> = serde_json::from_str(&achievements).unwrap();
-        Self {
+    /// read achievements from path(./steam_settings/achievements.json),
+    /// in any format of `schema::parse`
+    pub fn new(setting: &Setting) -> Result<Self, Problem> {
+        let achievements = Self::load(&setting.get_achievement_json_path())?;
+        Ok(Self {
             achievements,
-            image_dir: PathBuf::from(setting.get_image_dir()),
+            image_dirs: setting
+                .get_icon_search_dirs()
+                .into_iter()
+                .map(PathBuf::from)
+                .collect(),
             languages: setting.get_languages(),
-        }
+            rarity: rarity::load(setting),
+        })
+    }
+
+    fn load(path: &str) -> Result<Vec<AchievementRaw>, Problem> {
+        let unreadable = |error: String, position| Problem::Unreadable {
+            path: path.to_string(),
+            error,
+            position,
+        };
+        let text = fs::read_to_string(path).map_err(|e| unreadable(e.to_string(), None))?;
+        schema::parse(&text, schema::DEFAULT_SCHEMA_LANGUAGE)
+            .map_err(|e| unreadable(e.message, e.position))
     }
 
     /// get the achievement by name
@@ -103,97 +175,174 @@ impl AchievementsRaw {
             .find(|achievement| achievement.name == name)
     }
 
+    /// global unlock percent, if imported
+    pub fn get_rarity(&self, name: &str) -> Option<f64> {
+        self.rarity.get(name).copied()
+    }
+
+    /// title in the preferred language, or the achievement name if there is none
     pub fn get_display_name(&self, achievement: &AchievementRaw) -> String {
-        for language in &self.languages {
-            if let Some(display_name) = achievement.displayName.get(language) {
-                return display_name.clone();
-            }
-        }
-        for language in Setting::DEFAULT_LANGUAGES {
-            if let Some(display_name) = achievement.displayName.get(language) {
-                return display_name.clone();
-            }
-        }
-        achievement.displayName.values().next().unwrap().clone()
+        language::pick(&self.languages, &achievement.displayName)
+            .map(|(_, text)| text.clone())
+            .unwrap_or_else(|| achievement.name.clone())
     }
-    
+
     pub fn get_description(&self, achievement: &AchievementRaw) -> String {
-        for language in &self.languages {
-            if let Some(description) = achievement.description.get(language) {
-                return description.clone();
+        language::pick(&self.languages, &achievement.description)
+            .map(|(_, text)| text.clone())
+            .unwrap_or_default()
+    }
+
+    /// Steam languages used by any achievement of the schema, in STEAM_LANGUAGES order
+    pub fn get_schema_languages(&self) -> Vec<&'static str> {
+        language::STEAM_LANGUAGES
+            .iter()
+            .map(|(l, _)| *l)
+            .filter(|l| {
+                self.achievements
+                    .iter()
+                    .any(|a| a.displayName.contains_key(*l) || a.description.contains_key(*l))
+            })
+            .collect()
+    }
+
+    /// schema languages lacking a title or description for this achievement
+    pub fn get_missing_languages(&self, achievement: &AchievementRaw) -> Vec<&'static str> {
+        self.get_schema_languages()
+            .into_iter()
+            .filter(|l| {
+                !achievement.displayName.contains_key(*l)
+                    || !achievement.description.contains_key(*l)
+            })
+            .collect()
+    }
+
+    /// search path of an icon file of the achievement:
+    /// 1. {file}
+    /// 2. {image dir}/{file}, and {image dir}/{file name} for a file with directories or a url
+    /// 3. {image dir}/{achievement.name}
+    ///
+    /// for every image dir: the `image_dir` setting, the img/ dir next to it
+    /// and the `icon_search_dirs` setting. Only the last one for an empty file
+    pub fn get_icon_paths(&self, file: &str, achievement: &AchievementRaw) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        if !file.is_empty() {
+            paths.push(PathBuf::from(file));
+            let file_name = Path::new(file).file_name().filter(|n| *n != file);
+            for dir in &self.image_dirs {
+                paths.push(dir.join(file));
+                if let Some(name) = file_name {
+                    paths.push(dir.join(name));
+                }
             }
         }
-        for language in Setting::DEFAULT_LANGUAGES {
-            if let Some(description) = achievement.description.get(language) {
-                return description.clone();
-            }
+        for dir in &self.image_dirs {
+            paths.push(dir.join(&achievement.name));
         }
-        achievement.description.values().next().unwrap().clone()
+        // an absolute file is the same in every image dir
+        let mut seen = HashSet::new();
+        paths.retain(|path| seen.insert(path.clone()));
+        paths
     }
 
-    /// search path:
-    /// 1. achievement.icon
-    /// 2. ./steam_settings/achievement_images/{achievement.icon}
-    /// 3. ./steam_settings/achievement_images/{achievement.name}
-    /// Otherwise, return achievement.icon
-    pub fn get_icon(&self, achievement: &AchievementRaw) -> PathBuf {
-        // 1. achievement.icon
-        let path = PathBuf::from(&achievement.icon);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
-        }
-        // 2. ./steam_settings/achievement_images/{achievement.icon}
-        let mut path = self.image_dir.to_owned();
-        path.push(&achievement.icon);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
-        }
-        // 3. ./steam_settings/achievement_images/{achievement.name}
-        path.pop();
-        path.push(&achievement.name);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
+    /// the file of a search path, tried with image extensions when it has none,
+    /// and ignoring the case of the file name outside of Windows
+    pub fn find_icon_file(path: &Path) -> Option<PathBuf> {
+        let file_name = path.file_name()?.to_str()?;
+        let has_extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| ICON_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+        let mut names = vec![file_name.to_string()];
+        if !has_extension {
+            names.extend(
+                ICON_EXTENSIONS
+                    .iter()
+                    .map(|e| format!("{}.{}", file_name, e)),
+            );
         }
-        // Otherwise, return achievement.icon
-        achievement.icon.clone().into()
-    }
-
-    /// search path:
-    /// 1. achievement.icon_gray
-    /// 2. ./steam_settings/achievement_images/{achievement.icon_gray}
-    /// 3. ./steam_settings/achievement_images/{achievement.name}
-    /// Otherwise, return achievement.icon_gray
-    pub fn get_icon_gray(&self, achievement: &AchievementRaw) -> PathBuf {
-        // 1. achievement.icon
-        let path = PathBuf::from(&achievement.icon_gray);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
+        let dir = path.parent().unwrap_or(Path::new(""));
+        if let Some(found) = names.iter().map(|n| dir.join(n)).find(|p| p.is_file()) {
+            return Some(found);
         }
-        // 2. ./steam_settings/achievement_images/{achievement.icon}
-        let mut path = self.image_dir.to_owned();
-        path.push(&achievement.icon_gray);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
+        if cfg!(windows) {
+            return None;
         }
-        // 3. ./steam_settings/achievement_images/{achievement.name}
-        path.pop();
-        path.push(&achievement.name);
-        if path.exists() {
-            return std::path::absolute(path).unwrap();
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let names: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|p| p.is_file())
+            .filter_map(|p| {
+                let name = p.file_name()?.to_str()?.to_lowercase();
+                let rank = names.iter().position(|n| *n == name)?;
+                Some((rank, p))
+            })
+            .min()
+            .map(|(_, p)| p)
+    }
+
+    /// first existing file of the search path
+    fn find_icon(&self, file: &str, achievement: &AchievementRaw) -> Option<PathBuf> {
+        self.get_icon_paths(file, achievement)
+            .iter()
+            .find_map(|path| Self::find_icon_file(path))
+            .map(|path| std::path::absolute(path).unwrap())
+    }
+
+    /// achievement.icon in the search path of `get_icon_paths`
+    ///
+    /// Otherwise, return achievement.icon
+    pub fn get_icon(&self, achievement: &AchievementRaw) -> PathBuf {
+        self.find_icon(&achievement.icon, achievement)
+            .unwrap_or_else(|| achievement.icon.clone().into())
+    }
+
+    /// achievement.icon_gray in the search path of `get_icon_paths`
+    ///
+    /// Otherwise, None, the locked icon is made from the earned one
+    pub fn get_icon_gray(&self, achievement: &AchievementRaw) -> Option<PathBuf> {
+        if achievement.icon_gray.is_empty() {
+            return None;
         }
-        // Otherwise, return achievement.icon
-        achievement.icon_gray.clone().into()
+        self.find_icon(&achievement.icon_gray, achievement)
+    }
+
+    /// locked icon path of the achievement, empty if the schema has none
+    pub fn get_icon_gray_string(&self, achievement: &AchievementRaw) -> String {
+        self.get_icon_gray(achievement)
+            .map(|path| path.as_os_str().to_str().unwrap().to_string())
+            .unwrap_or_default()
+    }
+
+    /// number of schema achievements that are earned
+    pub fn count_earned(&self, achievements: &Achievements) -> usize {
+        self.achievements
+            .iter()
+            .filter(|a| {
+                achievements
+                    .achievements
+                    .get(&a.name)
+                    .is_some_and(|ac| ac.earned)
+            })
+            .count()
     }
 
     pub fn get_achievements(&self, achievements: &Achievements) -> Vec<AppAchievement> {
         let mut res = vec![];
         for a in &self.achievements {
-            let (state, date) = achievements
+            let (state, date, earned_time) = achievements
                 .achievements
                 .get(&a.name)
                 .and_then(|ac| {
                     if ac.earned {
-                        Some((ac.earned, ac.get_time_string()))
+                        Some((ac.earned, ac.get_time_string(), ac.earned_time))
                     } else {
                         None
                     }
@@ -202,12 +351,17 @@ impl AchievementsRaw {
             let icon = self.get_icon(a).as_os_str().to_str().unwrap().to_string();
             let aa = AppAchievement {
                 id: a.name.clone(),
-                icon: icon,
-                state: state,
-                date: date,
+                icon,
+                icon_gray: self.get_icon_gray_string(a),
+                state,
+                date,
+                earned_time,
                 title: self.get_display_name(a),
                 description: self.get_description(a),
                 visibility: a.hidden == "0",
+                missing_languages: self.get_missing_languages(a),
+                rarity: self.get_rarity(&a.name),
+                raw: a.clone(),
             };
             res.push(aa);
         }
@@ -215,20 +369,139 @@ impl AchievementsRaw {
     }
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Clone, Debug, Default)]
 pub struct AppAchievement {
     /// Achievement Id, like '81001' in Black Myth
     pub id: String,
     /// Achievement Icon Path
     pub icon: String,
+    /// Locked Icon Path, empty if the schema has none
+    pub icon_gray: String,
     /// Achievement State, true for earned, false for not earned
     pub state: bool,
     /// Achievement Earned Date, like '2021-08-19 12:00:00', empty for not earned
     pub date: String,
+    /// Achievement Earned Time in seconds since the unix epoch, 0 for not earned
+    pub earned_time: u64,
     /// Achievement Title
     pub title: String,
     /// Achievement Description
     pub description: String,
     /// Achievement Visiblity, true for visible, false for hidden
     pub visibility: bool,
+    /// Languages of the schema without a title or description for it
+    pub missing_languages: Vec<&'static str>,
+    /// Global unlock percent, None if no rarity data is imported
+    pub rarity: Option<f64>,
+    /// Schema entry it is built from, with the texts of every language
+    pub raw: AchievementRaw,
+}
+
+impl AppAchievement {
+    /// icon of the current state, the locked one is the earned one if the schema has none
+    pub fn get_state_icon(&self) -> &str {
+        if self.state || self.icon_gray.is_empty() {
+            &self.icon
+        } else {
+            &self.icon_gray
+        }
+    }
+
+    /// title in the given language, None if missing or the same as the shown title
+    pub fn get_title_in(&self, language: &str) -> Option<&String> {
+        self.raw
+            .displayName
+            .get(language)
+            .filter(|t| **t != self.title)
+    }
+
+    /// description in the given language, None if missing or the same as the shown description
+    pub fn get_description_in(&self, language: &str) -> Option<&String> {
+        self.raw
+            .description
+            .get(language)
+            .filter(|d| **d != self.description)
+    }
+}
+
+/// An achievement gained or lost, as sent by the file watcher
+#[derive(Clone, Debug, Default)]
+pub struct AchievementEvent {
+    pub kind: EventKind,
+    pub achievement: AppAchievement,
+    /// earned achievements of the game once this event happened
+    pub earned: usize,
+    /// achievements of the game
+    pub total: usize,
+}
+
+#[test]
+fn find_icon_by_hash_name() {
+    let dir = crate::temp_dir::TempDir::new("icon_paths");
+    let img = dir.join("img");
+    fs::create_dir_all(&img).unwrap();
+    fs::write(img.join("0A1B2C.JPG"), "").unwrap();
+    let raw = AchievementsRaw {
+        image_dirs: vec![dir.join("achievement_images"), img.clone()],
+        ..Default::default()
+    };
+    let achievement = AchievementRaw {
+        name: "ACH_WIN".to_string(),
+        icon: "https://cdn.example.com/apps/480/0a1b2c".to_string(),
+        ..Default::default()
+    };
+    let icon = raw.find_icon(&achievement.icon, &achievement);
+    let icon = icon.unwrap();
+    if cfg!(windows) {
+        assert!(icon.ends_with("0a1b2c.jpg"));
+    } else {
+        assert!(icon.ends_with("img/0A1B2C.JPG"));
+    }
+}
+
+#[test]
+fn unreadable_schema() {
+    let dir = crate::temp_dir::TempDir::new("unreadable");
+    let path = dir.join("achievements.json");
+    let path = path.to_str().unwrap();
+    assert!(matches!(
+        AchievementsRaw::load(path),
+        Err(Problem::Unreadable { position: None, .. })
+    ));
+    fs::write(path, "[\n  {\"name\": \"ACH_WIN\" \"hidden\": \"0\"}\n]").unwrap();
+    let problem = AchievementsRaw::load(path).unwrap_err();
+    assert_eq!(
+        problem.to_string(),
+        format!(
+            "{} can not be read: expected `,` or `}}` at line 2 column 22",
+            path
+        )
+    );
+    assert!(problem.is_error());
+}
+
+#[test]
+fn unlock_event_kinds() {
+    assert_eq!(EventKind::for_unlock(1, 10, None), EventKind::Unlock);
+    assert_eq!(EventKind::for_unlock(2, 10, Some(50.0)), EventKind::Unlock);
+    assert_eq!(EventKind::for_unlock(3, 10, None), EventKind::Progress);
+    assert_eq!(EventKind::for_unlock(5, 10, None), EventKind::Progress);
+    assert_eq!(EventKind::for_unlock(6, 10, None), EventKind::Unlock);
+    assert_eq!(EventKind::for_unlock(8, 10, None), EventKind::Progress);
+    assert_eq!(EventKind::for_unlock(9, 10, None), EventKind::Unlock);
+    assert_eq!(EventKind::for_unlock(10, 10, None), EventKind::Completion);
+    assert_eq!(
+        EventKind::for_unlock(10, 10, Some(1.0)),
+        EventKind::Completion
+    );
+    assert_eq!(
+        EventKind::for_unlock(2, 10, Some(1.0)),
+        EventKind::RareUnlock
+    );
+    assert_eq!(
+        EventKind::for_unlock(3, 10, Some(1.0)),
+        EventKind::RareUnlock
+    );
+    assert_eq!(EventKind::for_unlock(1, 1, None), EventKind::Completion);
+    assert_eq!(EventKind::for_unlock(0, 0, None), EventKind::Unlock);
 }
@@ -0,0 +1,128 @@
+use std::{fmt, fs, path::PathBuf};
+
+use crate::achievement::{AchievementRaw, AchievementsRaw};
+
+/// Why an icon can not be shown
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// no file in the search path
+    Missing,
+    /// the file exists but can not be read
+    Unreadable(String),
+    /// the file is read but is not a supported image
+    Undecodable(String),
+}
+
+/// An icon of the schema that is replaced by a placeholder
+#[derive(Debug, Clone)]
+pub struct IconIssue {
+    pub id: String,
+    /// "icon" or "icon_gray"
+    pub field: &'static str,
+    /// the file named by the schema
+    pub file: String,
+    pub problem: Problem,
+    /// paths looked at, in order, the last one is the file found if any;
+    /// each one is also tried with image extensions and in any case
+    pub tried: Vec<PathBuf>,
+}
+
+impl fmt::Display for IconIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problem = match &self.problem {
+            Problem::Missing => "missing".to_string(),
+            Problem::Unreadable(e) => format!("unreadable ({})", e),
+            Problem::Undecodable(e) => format!("undecodable ({})", e),
+        };
+        write!(f, "{} {} {:?}: {}", self.id, self.field, self.file, problem)?;
+        for path in &self.tried {
+            write!(f, "\n    {}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// check the icons of every achievement, the locked one only if the schema names it
+pub fn audit_icons(raw: &AchievementsRaw) -> Vec<IconIssue> {
+    let mut issues = vec![];
+    for achievement in &raw.achievements {
+        issues.extend(check_icon(raw, achievement, "icon", &achievement.icon));
+        if !achievement.icon_gray.is_empty() {
+            issues.extend(check_icon(
+                raw,
+                achievement,
+                "icon_gray",
+                &achievement.icon_gray,
+            ));
+        }
+    }
+    issues
+}
+
+fn check_icon(
+    raw: &AchievementsRaw,
+    achievement: &AchievementRaw,
+    field: &'static str,
+    file: &str,
+) -> Option<IconIssue> {
+    let mut tried = vec![];
+    let mut found = None;
+    for path in raw.get_icon_paths(file, achievement) {
+        tried.push(path.clone());
+        if let Some(path) = AchievementsRaw::find_icon_file(&path) {
+            if tried.last() != Some(&path) {
+                tried.push(path.clone());
+            }
+            found = Some(path);
+            break;
+        }
+    }
+    let problem = match found {
+        None => Problem::Missing,
+        Some(path) => match fs::read(&path) {
+            Err(e) => Problem::Unreadable(e.to_string()),
+            Ok(bytes) => match image::load_from_memory(&bytes) {
+                Err(e) => Problem::Undecodable(e.to_string()),
+                Ok(_) => return None,
+            },
+        },
+    };
+    Some(IconIssue {
+        id: achievement.name.clone(),
+        field,
+        file: file.to_string(),
+        problem,
+        tried,
+    })
+}
+
+#[test]
+fn audit_missing_and_broken_icons() {
+    let dir = crate::temp_dir::TempDir::new("audit");
+    image::RgbaImage::new(4, 4)
+        .save(dir.join("ok.png"))
+        .unwrap();
+    fs::write(dir.join("broken.png"), "not an image").unwrap();
+    let achievement = |name: &str, icon: &str, icon_gray: &str| AchievementRaw {
+        name: name.to_string(),
+        icon: icon.to_string(),
+        icon_gray: icon_gray.to_string(),
+        ..Default::default()
+    };
+    let raw = AchievementsRaw {
+        achievements: vec![
+            achievement("ACH_OK", "ok.png", ""),
+            achievement("ACH_BROKEN", "broken.png", "ok.png"),
+            achievement("ACH_MISSING", "ok.png", "gone.png"),
+        ],
+        image_dirs: vec![dir.to_path_buf()],
+        ..Default::default()
+    };
+    let issues = audit_icons(&raw);
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].id, "ACH_BROKEN");
+    assert!(matches!(issues[0].problem, Problem::Undecodable(_)));
+    assert_eq!(issues[1].field, "icon_gray");
+    assert_eq!(issues[1].problem, Problem::Missing);
+    assert_eq!(issues[1].tried.len(), 3);
+}
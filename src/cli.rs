@@ -0,0 +1,302 @@
+use std::{fs, path::PathBuf};
+
+use clap::Subcommand;
+
+use crate::{
+    achievement::{Achievements, AchievementsRaw},
+    audit, fetch,
+    i18n::I18n,
+    notes, schema,
+    setting::Setting,
+    showcase, validate,
+};
+
+/// Tasks run without opening the window
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Render a PNG of every earned achievement with the completion, or the card of one achievement.
+    Showcase {
+        /// Id of the achievement to render a card of, instead of the grid.
+        #[arg(long)]
+        id: Option<String>,
+        /// Path of the PNG. If not provided, it will be showcase.png or card_{id}.png in the data directory.
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// List achievements whose icons are missing, unreadable or not images. Exits with 1 if any.
+    Audit,
+    /// Check steam_settings/achievements.json and the save file. Exits with 1 if there is any error.
+    Validate,
+    /// Convert a GetSchemaForGame response or a SteamDB export to steam_settings/achievements.json.
+    Convert {
+        /// The schema file to convert.
+        input: String,
+        /// Path of the converted file. If not provided, it will be the achievements.json of the game.
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Steam language of the texts of the input, like schinese.
+        #[arg(long, default_value = schema::DEFAULT_SCHEMA_LANGUAGE)]
+        language: String,
+        /// Replace the output file if it exists.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Write the achievements with their state and notes, as CSV, or JSON for a .json output.
+    Export {
+        /// Path of the export. If not provided, it will be achievements.csv in the data directory.
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Download the icons of the schema into the image directory. Exits with 1 if any fails.
+    FetchIcons {
+        /// Url to download the icon files from, like a local mirror. `{appid}` is replaced. If not provided, it will be `icon_base_url` of the setting, or the Steam CDN.
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Downloads at the same time.
+        #[arg(short, long, default_value_t = fetch::DEFAULT_JOBS)]
+        jobs: usize,
+        /// Only list the downloads.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// run the command, returns the exit code of the process
+pub fn run(command: &Command, setting: &Setting) -> i32 {
+    match command {
+        Command::Showcase { id, output } => showcase(setting, id.as_deref(), output.as_deref()),
+        Command::Audit => audit(setting),
+        Command::Validate => validate(setting),
+        Command::Convert {
+            input,
+            output,
+            language,
+            force,
+        } => convert(setting, input, output.as_deref(), language, *force),
+        Command::Export { output } => export(setting, output.as_deref()),
+        Command::FetchIcons {
+            base_url,
+            jobs,
+            dry_run,
+        } => fetch_icons(setting, base_url.clone(), *jobs, *dry_run),
+    }
+}
+
+/// the schema, or None once the reason it can not be loaded is printed
+fn load_schema(setting: &Setting) -> Option<AchievementsRaw> {
+    AchievementsRaw::new(setting)
+        .map_err(|problem| println!("error: {}", problem))
+        .ok()
+}
+
+fn showcase(setting: &Setting, id: Option<&str>, output: Option<&str>) -> i32 {
+    let Some(achievements_raw) = load_schema(setting) else {
+        return 1;
+    };
+    let achievements = achievements_raw.get_achievements(&Achievements::new(setting));
+    let text = showcase::TextRenderer::new(&setting.get_fonts());
+    let (image, name) = match id {
+        Some(id) => {
+            let Some(ac) = achievements.iter().find(|ac| ac.id == id) else {
+                println!("Achievement {:?} is not found", id);
+                return 1;
+            };
+            let i18n = I18n::new(setting);
+            let card = showcase::Card {
+                achievement: ac,
+                headline: i18n.tr(if ac.state {
+                    "popup_gained"
+                } else {
+                    "popup_lost"
+                }),
+                note: "",
+                border: showcase::tier_color(ac),
+            };
+            (
+                showcase::render_card(&text, &card),
+                format!("card_{}.png", id),
+            )
+        }
+        None => (
+            showcase::render_grid(&text, &setting.get_game_name(), &achievements),
+            "showcase.png".to_string(),
+        ),
+    };
+    let path = match output {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(setting.get_data_dir()).join(name),
+    };
+    match showcase::save(&image, &path) {
+        Ok(()) => {
+            println!("Showcase is saved to {:?}", path);
+            0
+        }
+        Err(e) => {
+            println!("Can not save {:?}: {}", path, e);
+            1
+        }
+    }
+}
+
+fn audit(setting: &Setting) -> i32 {
+    let Some(achievements_raw) = load_schema(setting) else {
+        return 1;
+    };
+    let issues = audit::audit_icons(&achievements_raw);
+    for issue in &issues {
+        println!("{}", issue);
+    }
+    if issues.is_empty() {
+        println!(
+            "All icons of {} achievements are found",
+            achievements_raw.achievements.len()
+        );
+        0
+    } else {
+        println!(
+            "{} icons can not be shown, placeholders are used instead",
+            issues.len()
+        );
+        1
+    }
+}
+
+fn validate(setting: &Setting) -> i32 {
+    let Some(achievements_raw) = load_schema(setting) else {
+        return 1;
+    };
+    let problems = validate::validate(&achievements_raw, &Achievements::new(setting));
+    for problem in &problems {
+        let level = if problem.is_error() {
+            "error"
+        } else {
+            "warning"
+        };
+        println!("{}: {}", level, problem);
+    }
+    let errors = problems.iter().filter(|p| p.is_error()).count();
+    println!(
+        "{} achievements, {} errors, {} warnings",
+        achievements_raw.achievements.len(),
+        errors,
+        problems.len() - errors
+    );
+    if errors == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+fn convert(
+    setting: &Setting,
+    input: &str,
+    output: Option<&str>,
+    language: &str,
+    force: bool,
+) -> i32 {
+    let achievements = match fs::read_to_string(input)
+        .map_err(|e| e.to_string())
+        .and_then(|text| schema::parse(&text, language).map_err(|e| e.to_string()))
+    {
+        Ok(achievements) => achievements,
+        Err(e) => {
+            println!("Can not read {:?}: {}", input, e);
+            return 1;
+        }
+    };
+    let path = PathBuf::from(output.map_or(setting.get_achievement_json_path(), String::from));
+    if path.exists() && !force {
+        println!("{:?} exists, use --force to replace it", path);
+        return 1;
+    }
+    let json = serde_json::to_string_pretty(&achievements).unwrap();
+    let written = match path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        Some(dir) => fs::create_dir_all(dir).and_then(|_| fs::write(&path, json)),
+        None => fs::write(&path, json),
+    };
+    match written {
+        Ok(()) => {
+            println!(
+                "{} achievements are saved to {:?}",
+                achievements.len(),
+                path
+            );
+            0
+        }
+        Err(e) => {
+            println!("Can not save {:?}: {}", path, e);
+            1
+        }
+    }
+}
+
+fn fetch_icons(setting: &Setting, base_url: Option<String>, jobs: usize, dry_run: bool) -> i32 {
+    let Some(achievements_raw) = load_schema(setting) else {
+        return 1;
+    };
+    let image_dir = PathBuf::from(setting.get_image_dir());
+    let base_url = base_url.or(setting.get_icon_base_url());
+    let downloads = fetch::plan(
+        &achievements_raw,
+        base_url.as_deref(),
+        setting.get_app_id(),
+        &image_dir,
+    );
+    if dry_run {
+        for download in &downloads {
+            let state = if fetch::is_present(download) {
+                "present"
+            } else {
+                "download"
+            };
+            println!("{} {} -> {}", state, download.url, download.path.display());
+        }
+        println!("{} icons", downloads.len());
+        return 0;
+    }
+    let statuses = fetch::fetch_all(&downloads, jobs);
+    let count = |f: fn(&fetch::Status) -> bool| statuses.iter().filter(|s| f(s)).count();
+    let failed = count(|s| matches!(s, fetch::Status::Failed(_)));
+    println!(
+        "{} fetched, {} present, {} failed",
+        count(|s| *s == fetch::Status::Fetched),
+        count(|s| *s == fetch::Status::Present),
+        failed
+    );
+    for (download, status) in downloads.iter().zip(&statuses) {
+        if let fetch::Status::Failed(e) = status {
+            println!("Can not fetch {}: {}", download.url, e);
+        }
+    }
+    if failed == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+fn export(setting: &Setting, output: Option<&str>) -> i32 {
+    let Some(achievements_raw) = load_schema(setting) else {
+        return 1;
+    };
+    let achievements = achievements_raw.get_achievements(&Achievements::new(setting));
+    let path = match output {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(setting.get_data_dir()).join("achievements.csv"),
+    };
+    match notes::export(&achievements, &notes::Notes::new(setting), &path) {
+        Ok(()) => {
+            println!(
+                "{} achievements are exported to {:?}",
+                achievements.len(),
+                path
+            );
+            0
+        }
+        Err(e) => {
+            println!("Can not export to {:?}: {}", path, e);
+            1
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::achievement::AchievementsRaw;
+
+/// where Steam keeps the achievement icons of a game, `{appid}` is replaced
+pub const STEAM_CDN: &str =
+    "https://cdn.akamai.steamstatic.com/steamcommunity/public/images/apps/{appid}/";
+/// downloads at the same time, unless told otherwise
+pub const DEFAULT_JOBS: usize = 4;
+/// extension added to a hash without one, Steam icons are jpg
+const DEFAULT_EXTENSION: &str = "jpg";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An icon file of the schema to download
+#[derive(Debug, Clone, PartialEq)]
+pub struct Download {
+    pub url: String,
+    pub path: PathBuf,
+    /// sha1 of the content, when the file is named by it like the Steam icons
+    pub sha1: Option<String>,
+}
+
+/// What happened to a download
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    /// the file is already there and valid
+    Present,
+    Fetched,
+    Failed(String),
+}
+
+/// Downloads of every icon and locked icon of the schema, one per file.
+///
+/// Urls are fetched as they are, unless `base_url` is given, then their file name is
+/// fetched from it, like a local mirror. Hashes and file names are fetched from `base_url`,
+/// or from the Steam CDN.
+pub fn plan(
+    raw: &AchievementsRaw,
+    base_url: Option<&str>,
+    appid: u32,
+    image_dir: &Path,
+) -> Vec<Download> {
+    let base = base_url
+        .unwrap_or(STEAM_CDN)
+        .replace("{appid}", &appid.to_string());
+    let base = base.trim_end_matches('/');
+    let mut seen = HashSet::new();
+    let mut downloads = vec![];
+    for achievement in &raw.achievements {
+        for icon in [&achievement.icon, &achievement.icon_gray] {
+            let icon = icon.trim();
+            let is_url = icon.starts_with("http://") || icon.starts_with("https://");
+            let Some(name) = icon.rsplit('/').next().filter(|n| !n.is_empty()) else {
+                continue;
+            };
+            let name = if Path::new(name).extension().is_some() {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, DEFAULT_EXTENSION)
+            };
+            let url = if is_url && base_url.is_none() {
+                icon.to_string()
+            } else {
+                format!("{}/{}", base, name)
+            };
+            let path = image_dir.join(&name);
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let stem = name.split('.').next().unwrap_or_default().to_lowercase();
+            let is_sha1 = stem.len() == 40 && stem.chars().all(|c| c.is_ascii_hexdigit());
+            downloads.push(Download {
+                url,
+                path,
+                sha1: is_sha1.then_some(stem),
+            });
+        }
+    }
+    downloads
+}
+
+/// run the downloads on `jobs` threads, the statuses are in the order of the downloads
+pub fn fetch_all(downloads: &[Download], jobs: usize) -> Vec<Status> {
+    let next = Arc::new(Mutex::new(0));
+    let statuses = Arc::new(Mutex::new(vec![Status::Present; downloads.len()]));
+    let downloads = Arc::new(downloads.to_vec());
+    let workers: Vec<_> = (0..jobs.clamp(1, downloads.len().max(1)))
+        .map(|_| {
+            let (next, statuses, downloads) = (next.clone(), statuses.clone(), downloads.clone());
+            thread::spawn(move || loop {
+                let i = {
+                    let mut next = next.lock().unwrap();
+                    *next += 1;
+                    *next - 1
+                };
+                let Some(download) = downloads.get(i) else {
+                    break;
+                };
+                let status = match fetch(download) {
+                    Ok(status) => status,
+                    Err(e) => Status::Failed(e),
+                };
+                println!("{:?} {}", status, download.url);
+                statuses.lock().unwrap()[i] = status;
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    let statuses = statuses.lock().unwrap().clone();
+    statuses
+}
+
+/// whether the file of the download is there and valid
+pub fn is_present(download: &Download) -> bool {
+    fs::read(&download.path).is_ok_and(|bytes| check(download, &bytes).is_ok())
+}
+
+/// the sha1 of the name when there is one, otherwise the content must be an image
+fn check(download: &Download, bytes: &[u8]) -> Result<(), String> {
+    match &download.sha1 {
+        Some(sha1) => {
+            let digest = sha1_smol::Sha1::from(bytes).digest().to_string();
+            if digest == *sha1 {
+                Ok(())
+            } else {
+                Err(format!("sha1 is {}, expected {}", digest, sha1))
+            }
+        }
+        None => image::load_from_memory(bytes)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// download into `{path}.part`, continuing a part left by a previous run,
+/// and move it in place once it is checked
+fn fetch(download: &Download) -> Result<Status, String> {
+    if is_present(download) {
+        return Ok(Status::Present);
+    }
+    let mut part = download.path.clone().into_os_string();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    if let Some(dir) = part.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let mut request = ureq::get(&download.url).timeout(TIMEOUT);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={}-", offset));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        // the part is already the whole file
+        Err(ureq::Error::Status(416, _)) if offset > 0 => {
+            return finish(download, &part);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut file = if response.status() == 206 {
+        fs::OpenOptions::new().append(true).open(&part)
+    } else {
+        fs::File::create(&part)
+    }
+    .map_err(|e| e.to_string())?;
+    io::copy(&mut response.into_reader(), &mut file).map_err(|e| e.to_string())?;
+    file.flush().map_err(|e| e.to_string())?;
+    finish(download, &part)
+}
+
+fn finish(download: &Download, part: &Path) -> Result<Status, String> {
+    let bytes = fs::read(part).map_err(|e| e.to_string())?;
+    if let Err(e) = check(download, &bytes) {
+        // a broken part would only be continued
+        let _ = fs::remove_file(part);
+        return Err(e);
+    }
+    fs::rename(part, &download.path).map_err(|e| e.to_string())?;
+    Ok(Status::Fetched)
+}
+
+#[test]
+fn fetch_from_local_mirror() {
+    use crate::achievement::AchievementRaw;
+    let icon = b"not really a jpg".to_vec();
+    let sha1 = sha1_smol::Sha1::from(&icon).digest().to_string();
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/480/", server.server_addr().to_ip().unwrap());
+    let (served, name) = (icon.clone(), format!("{}.jpg", sha1));
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let range = request
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("Range"))
+                .and_then(|h| {
+                    h.value
+                        .as_str()
+                        .strip_prefix("bytes=")?
+                        .strip_suffix('-')?
+                        .parse()
+                        .ok()
+                });
+            let response = match range {
+                Some(start) => {
+                    tiny_http::Response::from_data(served[start..].to_vec()).with_status_code(206)
+                }
+                None if request.url().ends_with(&name) => {
+                    tiny_http::Response::from_data(served.clone())
+                }
+                None => tiny_http::Response::from_data(vec![]).with_status_code(404),
+            };
+            request.respond(response).unwrap();
+        }
+    });
+
+    let dir = crate::temp_dir::TempDir::new("fetch");
+    let raw = AchievementsRaw {
+        achievements: vec![AchievementRaw {
+            name: "ACH_WIN".to_string(),
+            icon: format!("https://cdn.example.com/apps/480/{}.jpg", sha1),
+            icon_gray: "missing".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let downloads = plan(&raw, Some(&base), 480, &dir);
+    assert_eq!(downloads.len(), 2);
+    assert_eq!(downloads[0].url, format!("{}{}.jpg", base, sha1));
+    assert_eq!(downloads[1].path, dir.join("missing.jpg"));
+
+    // half of the icon is left by a previous run
+    let part = dir.join(format!("{}.jpg.part", sha1));
+    fs::write(&part, &icon[..5]).unwrap();
+    let statuses = fetch_all(&downloads[..1], 2);
+    assert_eq!(statuses, [Status::Fetched]);
+    assert_eq!(fs::read(&downloads[0].path).unwrap(), icon);
+    assert!(!part.exists());
+    assert_eq!(fetch_all(&downloads, 2)[0], Status::Present);
+    assert!(matches!(fetch_all(&downloads, 2)[1], Status::Failed(_)));
+}
@@ -24,20 +24,24 @@ fn load_fonts(fonts: &mut FontDefinitions, handle: Handle, name: String) {
     }
 }
 
-pub fn load_system_font(ctx: &Context, setting: &Setting) {
+/// fonts of the setting that are installed, as (handle, name)
+fn find_system_fonts(fonts: &[String]) -> Vec<(Handle, String)> {
     let font_family = SystemSource::new();
     let property = Properties::new();
-    let fonts: Vec<_> = setting
-        .get_fonts()
-        .into_iter()
-        .map(|s| ([FamilyName::Title(s.clone())], s))
+    fonts
+        .iter()
+        .map(|s| ([FamilyName::Title(s.clone())], s.clone()))
         .filter_map(|(fm, s)| {
             font_family
                 .select_best_match(&fm, &property)
                 .ok()
-                .and_then(|f| Some((f, s)))
+                .map(|f| (f, s))
         })
-        .collect();
+        .collect()
+}
+
+pub fn load_system_font(ctx: &Context, setting: &Setting) {
+    let fonts = find_system_fonts(&setting.get_fonts());
     println!("Font Family List {:#?}", fonts);
     let mut font = FontDefinitions::default();
     for (handle, name) in fonts {
@@ -46,6 +50,27 @@ pub fn load_system_font(ctx: &Context, setting: &Setting) {
     ctx.set_fonts(font);
 }
 
+/// Font files in the order the UI falls back through them, as (bytes, face index):
+/// the built-in fonts of egui, then the system fonts of the setting.
+pub fn load_font_data(fonts: &[String]) -> Vec<(Vec<u8>, u32)> {
+    let builtin = FontDefinitions::default();
+    let mut res: Vec<(Vec<u8>, u32)> = builtin.families[&FontFamily::Proportional]
+        .iter()
+        .filter_map(|name| builtin.font_data.get(name))
+        .map(|data| (data.font.to_vec(), data.index))
+        .collect();
+    for (handle, name) in find_system_fonts(fonts) {
+        match handle {
+            Handle::Memory { bytes, font_index } => res.push((bytes.to_vec(), font_index)),
+            Handle::Path { path, font_index } => match std::fs::read(&path) {
+                Ok(bytes) => res.push((bytes, font_index)),
+                Err(e) => println!("Can not read font {} {:?}: {}", name, path, e),
+            },
+        }
+    }
+    res
+}
+
 #[test]
 pub fn print_font_family(){
     let font_family = SystemSource::new();
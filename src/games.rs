@@ -0,0 +1,103 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::setting::Setting;
+
+const GAME_NAME: &str = "game.json";
+
+/// A game the reminder has been run for, kept in `{data dir}/game.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Game {
+    pub appid: u32,
+    pub name: String,
+    /// where the reminder was started, holding steam_settings/
+    pub dir: PathBuf,
+}
+
+impl Game {
+    /// the game of this run
+    pub fn current(setting: &Setting) -> Self {
+        Self {
+            appid: setting.get_app_id(),
+            name: setting.get_game_name(),
+            dir: env::current_dir().unwrap_or_default(),
+        }
+    }
+
+    /// write it to the data dir, so it can be switched to later
+    pub fn remember(&self, setting: &Setting) {
+        let dir = PathBuf::from(setting.get_data_dir());
+        let written = fs::create_dir_all(&dir).and_then(|_| {
+            fs::write(
+                dir.join(GAME_NAME),
+                serde_json::to_string_pretty(self).unwrap(),
+            )
+        });
+        if let Err(e) = written {
+            println!("Can not remember the game in {:?}: {}", dir, e);
+        }
+    }
+
+    /// start another reminder for this game, in its dir and with the same setting file
+    pub fn launch(&self, setting: &Setting) -> Result<(), String> {
+        let exe = env::current_exe().map_err(|e| e.to_string())?;
+        let setting_file = setting.get_setting_file();
+        let setting_file = fs::canonicalize(&setting_file).unwrap_or(setting_file.into());
+        Command::new(exe)
+            .current_dir(&self.dir)
+            .arg("-a")
+            .arg(self.appid.to_string())
+            .arg("-s")
+            .arg(setting_file)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// every game remembered in the data dirs, by name
+pub fn known(setting: &Setting) -> Vec<Game> {
+    load_all(Path::new(&setting.get_reminder_dir()))
+}
+
+fn load_all(root: &Path) -> Vec<Game> {
+    let mut games: Vec<Game> = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let text = fs::read_to_string(entry.path().join(GAME_NAME)).ok()?;
+            serde_json::from_str(&text).ok()
+        })
+        .collect();
+    games.sort_by(|a, b| a.name.cmp(&b.name).then(a.appid.cmp(&b.appid)));
+    games
+}
+
+#[test]
+fn known_games() {
+    let root = crate::temp_dir::TempDir::new("games");
+    for (appid, name) in [(480, "Spacewar"), (570, "Dota 2")] {
+        let game = Game {
+            appid,
+            name: name.to_string(),
+            dir: PathBuf::from("/games").join(name),
+        };
+        fs::create_dir_all(root.join(appid.to_string())).unwrap();
+        fs::write(
+            root.join(appid.to_string()).join(GAME_NAME),
+            serde_json::to_string(&game).unwrap(),
+        )
+        .unwrap();
+    }
+    fs::create_dir_all(root.join("730")).unwrap();
+    let games = load_all(&root);
+    let names: Vec<&str> = games.iter().map(|g| g.name.as_str()).collect();
+    assert_eq!(names, ["Dota 2", "Spacewar"]);
+    assert_eq!(games[1].appid, 480);
+}
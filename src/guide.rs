@@ -0,0 +1,267 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use eframe::egui;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+use crate::{achievement::AppAchievement, setting::Setting};
+
+const GUIDE_NAME: &str = "guide.md";
+
+/// Walkthrough of the game, from `{data dir}/guide.md`.
+///
+/// The guide of an achievement is the part under a heading naming its id or its title,
+/// like `## ACH_WIN`, down to the next heading of the same level or above.
+pub struct Guide {
+    path: PathBuf,
+    /// markdown by lowercase heading
+    sections: HashMap<String, String>,
+    /// modification time of the file read, None if there is none
+    modified: Option<SystemTime>,
+}
+
+impl Guide {
+    pub fn new(setting: &Setting) -> Self {
+        Self::load(PathBuf::from(setting.get_data_dir()).join(GUIDE_NAME))
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let modified = modified(&path);
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        Self {
+            sections: sections(&text),
+            path,
+            modified,
+        }
+    }
+
+    /// read the file again if it has changed since, so edits show up while the reminder runs
+    pub fn reload(&mut self) {
+        if modified(&self.path) != self.modified {
+            *self = Self::load(std::mem::take(&mut self.path));
+        }
+    }
+
+    /// where the guide is read from
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, ac: &AppAchievement) -> Option<&str> {
+        [&ac.id, &ac.title]
+            .iter()
+            .find_map(|key| self.sections.get(&key.trim().to_lowercase()))
+            .map(|s| s.as_str())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// level of a markdown heading line, like 2 for "## ACH_WIN"
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' '))).then_some(level)
+}
+
+/// the text under every heading, the deeper headings included
+fn sections(text: &str) -> HashMap<String, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut sections = HashMap::new();
+    // (level, heading, first line) of the headings whose section is not closed yet
+    let mut open: Vec<(usize, String, usize)> = vec![];
+    let mut fenced = false;
+    let mut close = |open: &mut Vec<(usize, String, usize)>, level: usize, end: usize| {
+        while open.last().is_some_and(|(l, _, _)| *l >= level) {
+            let (_, heading, start) = open.pop().unwrap();
+            let body = lines[start..end].join("\n").trim().to_string();
+            sections.entry(heading).or_insert(body);
+        }
+    };
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            fenced = !fenced;
+        }
+        if fenced {
+            continue;
+        }
+        if let Some(level) = heading_level(line) {
+            close(&mut open, level, i);
+            let heading = line[level..].trim().trim_end_matches('#').trim();
+            open.push((level, heading.to_lowercase(), i + 1));
+        }
+    }
+    close(&mut open, 1, lines.len());
+    sections
+}
+
+/// A piece of a paragraph
+enum Inline {
+    Text(egui::RichText),
+    Link(String, String),
+    Image(String),
+}
+
+/// Draw markdown with headings, emphasis, code, lists, links and images;
+/// relative image paths are read from `dir`.
+pub fn show(ui: &mut egui::Ui, markdown: &str, dir: &Path) {
+    let mut block: Vec<Inline> = vec![];
+    let mut heading: Option<HeadingLevel> = None;
+    let (mut strong, mut emphasis, mut strike, mut code_block) = (false, false, false, false);
+    let mut link: Option<String> = None;
+    let mut image: Option<String> = None;
+    // next number of each open list, None for bullets
+    let mut lists: Vec<Option<u64>> = vec![];
+    let flush = |ui: &mut egui::Ui, block: &mut Vec<Inline>| {
+        if block.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            for inline in block.drain(..) {
+                match inline {
+                    Inline::Text(text) => {
+                        ui.label(text);
+                    }
+                    Inline::Link(text, url) => {
+                        ui.hyperlink_to(text, url);
+                    }
+                    Inline::Image(uri) => {
+                        ui.add(egui::Image::new(uri).max_width(ui.available_width()));
+                    }
+                }
+            }
+        });
+    };
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Start(Tag::Paragraph) => flush(ui, &mut block),
+            Event::Start(Tag::Heading { level, .. }) => {
+                flush(ui, &mut block);
+                heading = Some(level);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                flush(ui, &mut block);
+                code_block = true;
+            }
+            Event::Start(Tag::List(first)) => {
+                flush(ui, &mut block);
+                lists.push(first);
+            }
+            Event::Start(Tag::Item) => {
+                flush(ui, &mut block);
+                let indent = "    ".repeat(lists.len().saturating_sub(1));
+                let marker = match lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}{}. ", indent, *n - 1)
+                    }
+                    _ => format!("{}• ", indent),
+                };
+                block.push(Inline::Text(egui::RichText::new(marker)));
+            }
+            Event::Start(Tag::Strong) => strong = true,
+            Event::Start(Tag::Emphasis) => emphasis = true,
+            Event::Start(Tag::Strikethrough) => strike = true,
+            Event::Start(Tag::Link { dest_url, .. }) => link = Some(dest_url.to_string()),
+            Event::Start(Tag::Image { dest_url, .. }) => image = Some(dest_url.to_string()),
+            Event::End(TagEnd::Paragraph | TagEnd::Item) => flush(ui, &mut block),
+            Event::End(TagEnd::Heading(_)) => {
+                flush(ui, &mut block);
+                heading = None;
+            }
+            Event::End(TagEnd::CodeBlock) => code_block = false,
+            Event::End(TagEnd::List(_)) => {
+                flush(ui, &mut block);
+                lists.pop();
+            }
+            Event::End(TagEnd::Strong) => strong = false,
+            Event::End(TagEnd::Emphasis) => emphasis = false,
+            Event::End(TagEnd::Strikethrough) => strike = false,
+            Event::End(TagEnd::Link) => link = None,
+            Event::End(TagEnd::Image) => {
+                if let Some(url) = image.take() {
+                    let uri = if url.contains("://") {
+                        url
+                    } else {
+                        format!("file://{}", dir.join(url).display())
+                    };
+                    block.push(Inline::Image(uri));
+                }
+            }
+            Event::Text(text) if code_block => {
+                ui.label(egui::RichText::new(text.trim_end()).code());
+            }
+            // the alt text of an image is not shown
+            Event::Text(_) if image.is_some() => {}
+            Event::Text(text) | Event::Code(text) if link.is_some() => {
+                block.push(Inline::Link(text.to_string(), link.clone().unwrap()));
+            }
+            Event::Text(text) => {
+                let mut text = egui::RichText::new(text.as_ref());
+                if let Some(level) = heading {
+                    let size = match level {
+                        HeadingLevel::H1 => 22.0,
+                        HeadingLevel::H2 => 19.0,
+                        _ => 16.0,
+                    };
+                    text = text.size(size).strong();
+                }
+                if strong {
+                    text = text.strong();
+                }
+                if emphasis {
+                    text = text.italics();
+                }
+                if strike {
+                    text = text.strikethrough();
+                }
+                block.push(Inline::Text(text));
+            }
+            Event::Code(text) => {
+                block.push(Inline::Text(egui::RichText::new(text.as_ref()).code()))
+            }
+            Event::SoftBreak => block.push(Inline::Text(egui::RichText::new(" "))),
+            Event::HardBreak => flush(ui, &mut block),
+            Event::Rule => {
+                flush(ui, &mut block);
+                ui.separator();
+            }
+            _ => {}
+        }
+    }
+    flush(ui, &mut block);
+}
+
+#[test]
+fn guide_sections() {
+    let text = "# Walkthrough\nIntro\n\n## ACH_BRIDGE\nCross the bridge.\n\
+        ```\n# not a heading\n```\n### Tips\nRun.\n## Win the Game\nJust win.\n";
+    let sections = sections(text);
+    assert_eq!(
+        sections["ach_bridge"],
+        "Cross the bridge.\n```\n# not a heading\n```\n### Tips\nRun."
+    );
+    assert_eq!(sections["tips"], "Run.");
+    assert_eq!(sections["win the game"], "Just win.");
+    assert!(sections["walkthrough"].starts_with("Intro"));
+    assert!(!sections.contains_key("not a heading"));
+    assert_eq!(heading_level("#hashtag"), None);
+}
+
+#[test]
+fn reload_changed_guide() {
+    let dir = crate::temp_dir::TempDir::new("guide");
+    let path = dir.join(GUIDE_NAME);
+    let mut guide = Guide::load(path.clone());
+    assert!(guide.sections.is_empty());
+    fs::write(&path, "## ACH_WIN\nJust win.\n").unwrap();
+    guide.reload();
+    assert_eq!(guide.sections["ach_win"], "Just win.");
+}
@@ -0,0 +1,99 @@
+use std::{fs, io::Write, path::PathBuf, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{achievement::AchievementEvent, setting::Setting};
+
+const HISTORY_NAME: &str = "history.jsonl";
+const PERFECT_GAME_NAME: &str = "perfect_game.txt";
+
+/// An achievement event, one line of `{data dir}/history.jsonl`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// when the event was noticed, in seconds since the unix epoch
+    pub time: u64,
+    /// event kind, like 'unlock' or 'relock'
+    pub event: String,
+    pub id: String,
+    pub title: String,
+    pub earned: usize,
+    pub total: usize,
+}
+
+/// Every achievement event noticed for the game, kept in the data dir.
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn new(setting: &Setting) -> Self {
+        Self {
+            dir: PathBuf::from(setting.get_data_dir()),
+        }
+    }
+
+    /// append the event to the history file, returns the entry
+    pub fn record(&self, event: &AchievementEvent) -> Entry {
+        let entry = Entry {
+            time: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            event: event.kind.name().to_string(),
+            id: event.achievement.id.clone(),
+            title: event.achievement.title.clone(),
+            earned: event.earned,
+            total: event.total,
+        };
+        let path = self.dir.join(HISTORY_NAME);
+        let written = fs::create_dir_all(&self.dir).and_then(|_| {
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)?;
+            writeln!(file, "{}", serde_json::to_string(&entry).unwrap())
+        });
+        if let Err(e) = written {
+            println!("Can not write history {:?}: {}", path, e);
+        }
+        entry
+    }
+
+    /// every entry, oldest first; broken lines are skipped
+    pub fn load(&self) -> Vec<Entry> {
+        fs::read_to_string(self.dir.join(HISTORY_NAME))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// keep the summary card of a 100% completion, returns where it is saved
+    pub fn save_perfect_game(&self, summary: &str) -> Option<PathBuf> {
+        let path = self.dir.join(PERFECT_GAME_NAME);
+        match fs::create_dir_all(&self.dir).and_then(|_| fs::write(&path, summary)) {
+            Ok(()) => Some(path),
+            Err(e) => {
+                println!("Can not save {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+#[test]
+fn record_and_load_history() {
+    let dir = crate::temp_dir::TempDir::new("history");
+    let history = History {
+        dir: dir.to_path_buf(),
+    };
+    let mut event = AchievementEvent::default();
+    event.achievement.id = "ACH_WIN".to_string();
+    history.record(&event);
+    event.kind = crate::achievement::EventKind::Relock;
+    history.record(&event);
+    let entries = history.load();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].event, "relock");
+    assert_eq!(entries[1].id, "ACH_WIN");
+}
@@ -0,0 +1,286 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::setting::Setting;
+
+type Catalog = HashMap<String, String>;
+
+/// Language used when a string is missing from the selected pack
+const FALLBACK_LANGUAGE: &str = "english";
+
+const ENGLISH: [(&str, &str); 83] = [
+    ("run_reminder", "⬤ Run Reminder!"),
+    ("close", "⬤ Close!"),
+    ("light_mode", "Go Light Mode!"),
+    ("dark_mode", "Go Dark Mode!"),
+    ("ui_language", "UI Language"),
+    ("secondary_language", "Secondary Language"),
+    ("none", "None"),
+    ("mute", "Mute"),
+    ("volume", "Volume"),
+    ("preview", "▶ Preview"),
+    ("audio_device", "Audio Device"),
+    ("default", "Default"),
+    ("speak", "Speak achievements"),
+    ("send_test", "Send test webhook"),
+    ("unlock", "Unlock"),
+    ("relock", "Relock"),
+    ("rare_unlock", "Rare unlock"),
+    ("completion", "100% completion"),
+    ("progress", "Progress milestone"),
+    ("statistics", "Statistics"),
+    ("stat_completion", "Completion"),
+    ("stat_hidden", "Hidden earned"),
+    ("stat_first", "First unlock"),
+    ("stat_latest", "Latest unlock"),
+    ("stat_longest_gap", "Longest gap"),
+    ("stat_average_gap", "Average time between unlocks"),
+    ("stat_per_day", "Unlocks per day"),
+    ("col_id", "Id"),
+    ("col_icon", "Icon"),
+    ("col_state", "State"),
+    ("col_date", "Date"),
+    ("col_rarity", "Global %"),
+    ("col_visibility", "Visibility"),
+    ("col_title", "Title"),
+    ("missing_languages", "Missing languages:"),
+    ("col_description", "Description"),
+    ("achieved", "Achieved!"),
+    ("popup_window", "Achievement Window"),
+    (
+        "popup_summary",
+        "Achievement {state}!\nId: \t{id}\nTitle: \t{title}\nDescrition: \t{description}\n --- Click to jump ---",
+    ),
+    ("popup_state_get", "Get"),
+    ("popup_state_lose", "Lose"),
+    ("popup_gained", "Achievement Gained! CONGRATS!"),
+    ("popup_lost", "Achievement Seems Disappeared!"),
+    ("perfect_game", "🏆 Perfect Game!"),
+    ("copy_summary", "Copy summary"),
+    ("save_showcase", "🖼 Save showcase"),
+    ("saved_to", "Saved to"),
+    ("rendering", "Rendering…"),
+    ("tab_achievements", "Achievements"),
+    ("tab_problems", "⚠ Problems"),
+    ("no_problems", "No problems in achievements.json"),
+    ("error", "Error"),
+    ("warning", "Warning"),
+    ("col_notes", "Notes"),
+    ("search", "🔍 Search"),
+    ("all_tags", "All tags"),
+    ("planned", "Planned"),
+    ("planned_only", "Planned only"),
+    ("edit_note", "Notes of"),
+    ("tags", "Tags"),
+    ("save", "Save"),
+    ("cancel", "Cancel"),
+    ("export", "Export"),
+    ("guide", "Guide"),
+    ("no_guide", "No guide yet, add a section \"## {id}\" to {path}"),
+    ("all_languages", "All languages"),
+    ("icon_paths", "Icon paths"),
+    ("raw_json", "Raw JSON"),
+    ("earned_icon", "Earned"),
+    ("locked_icon", "Locked"),
+    ("earned_time", "Earned time"),
+    ("hidden", "Hidden"),
+    ("history", "History"),
+    ("no_history", "No event recorded yet"),
+    ("view_table", "☰ Table"),
+    ("view_gallery", "🖼 Gallery"),
+    ("tile_size", "Tile size"),
+    ("not_earned", "Not earned yet"),
+    ("tray_open", "Open main window"),
+    ("tray_popup", "Show popups"),
+    ("tray_switch_game", "Switch game"),
+    ("tray_quit", "Quit"),
+    (
+        "perfect_summary",
+        "🏆 Perfect game: {game}\nAll {total} achievements, completed on {date}\n{span} from the first unlock ({first}) to the last",
+    ),
+];
+
+const SCHINESE: [(&str, &str); 83] = [
+    ("run_reminder", "⬤ 启动提醒！"),
+    ("close", "⬤ 关闭！"),
+    ("light_mode", "切换到浅色模式！"),
+    ("dark_mode", "切换到深色模式！"),
+    ("ui_language", "界面语言"),
+    ("secondary_language", "第二语言"),
+    ("none", "无"),
+    ("mute", "静音"),
+    ("volume", "音量"),
+    ("preview", "▶ 试听"),
+    ("audio_device", "音频设备"),
+    ("default", "默认"),
+    ("speak", "语音播报成就"),
+    ("send_test", "发送测试 Webhook"),
+    ("unlock", "解锁"),
+    ("relock", "重新锁定"),
+    ("rare_unlock", "稀有解锁"),
+    ("completion", "全成就达成"),
+    ("progress", "进度里程碑"),
+    ("statistics", "统计"),
+    ("stat_completion", "完成度"),
+    ("stat_hidden", "已获得隐藏成就"),
+    ("stat_first", "首次解锁"),
+    ("stat_latest", "最近解锁"),
+    ("stat_longest_gap", "最长间隔"),
+    ("stat_average_gap", "平均解锁间隔"),
+    ("stat_per_day", "每日解锁数"),
+    ("col_id", "编号"),
+    ("col_icon", "图标"),
+    ("col_state", "状态"),
+    ("col_date", "日期"),
+    ("col_rarity", "全球解锁率"),
+    ("col_visibility", "可见性"),
+    ("col_title", "标题"),
+    ("missing_languages", "缺少语言："),
+    ("col_description", "描述"),
+    ("achieved", "已达成！"),
+    ("popup_window", "成就窗口"),
+    (
+        "popup_summary",
+        "成就{state}！\n编号：\t{id}\n标题：\t{title}\n描述：\t{description}\n --- 点击跳转 ---",
+    ),
+    ("popup_state_get", "获得"),
+    ("popup_state_lose", "失去"),
+    ("popup_gained", "获得成就！恭喜！"),
+    ("popup_lost", "成就似乎消失了！"),
+    ("perfect_game", "🏆 全成就达成！"),
+    ("copy_summary", "复制总结"),
+    ("save_showcase", "🖼 保存成就展示图"),
+    ("saved_to", "已保存到"),
+    ("rendering", "正在生成…"),
+    ("tab_achievements", "成就"),
+    ("tab_problems", "⚠ 问题"),
+    ("no_problems", "achievements.json 没有问题"),
+    ("error", "错误"),
+    ("warning", "警告"),
+    ("col_notes", "笔记"),
+    ("search", "🔍 搜索"),
+    ("all_tags", "全部标签"),
+    ("planned", "计划中"),
+    ("planned_only", "仅计划中"),
+    ("edit_note", "笔记："),
+    ("tags", "标签"),
+    ("save", "保存"),
+    ("cancel", "取消"),
+    ("export", "导出"),
+    ("guide", "攻略"),
+    ("no_guide", "还没有攻略，可以在 {path} 中添加 \"## {id}\" 一节"),
+    ("all_languages", "所有语言"),
+    ("icon_paths", "图标路径"),
+    ("raw_json", "原始 JSON"),
+    ("earned_icon", "已解锁"),
+    ("locked_icon", "未解锁"),
+    ("earned_time", "解锁时间戳"),
+    ("hidden", "隐藏"),
+    ("history", "历史记录"),
+    ("no_history", "还没有记录到事件"),
+    ("view_table", "☰ 表格"),
+    ("view_gallery", "🖼 图标墙"),
+    ("tile_size", "图标大小"),
+    ("not_earned", "尚未解锁"),
+    ("tray_open", "打开主窗口"),
+    ("tray_popup", "显示弹窗"),
+    ("tray_switch_game", "切换游戏"),
+    ("tray_quit", "退出"),
+    (
+        "perfect_summary",
+        "🏆 全成就达成：{game}\n全部 {total} 个成就，完成于 {date}\n从首个成就（{first}）到最后一个用时 {span}",
+    ),
+];
+
+/// Catalog of the strings shown in the UI chrome (buttons, headers, popups).
+///
+/// English and Simplified Chinese are built in; extra packs are read from
+/// `{setting dir}/languages/{language}.json`, a flat object of `key: text`.
+/// A pack with the name of a built-in language overrides its entries.
+#[derive(Debug)]
+pub struct I18n {
+    language: String,
+    packs: HashMap<String, Catalog>,
+}
+
+impl I18n {
+    pub fn new(setting: &Setting) -> Self {
+        let mut packs = HashMap::new();
+        packs.insert("english".to_string(), Self::builtin(&ENGLISH));
+        packs.insert("schinese".to_string(), Self::builtin(&SCHINESE));
+        Self::load_packs(&mut packs, PathBuf::from(setting.get_language_pack_dir()));
+        Self {
+            language: setting.get_ui_language(),
+            packs,
+        }
+    }
+
+    fn builtin(entries: &[(&str, &str)]) -> Catalog {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// read every `*.json` under dir, the file stem is the language name
+    fn load_packs(packs: &mut HashMap<String, Catalog>, dir: PathBuf) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return;
+        };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(language) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let catalog: Option<Catalog> = fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok());
+            match catalog {
+                Some(catalog) => {
+                    println!("Load language pack {:?}", path);
+                    packs
+                        .entry(language.to_string())
+                        .or_default()
+                        .extend(catalog);
+                }
+                None => println!("Invalid language pack {:?}", path),
+            }
+        }
+    }
+
+    /// Translate key into the current language.
+    /// Falls back to english, then to the key itself.
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        [self.language.as_str(), FALLBACK_LANGUAGE]
+            .iter()
+            .find_map(|language| self.packs.get(*language)?.get(key))
+            .map(|s| s.as_str())
+            .unwrap_or(key)
+    }
+
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn set_language(&mut self, language: &str) {
+        self.language = language.to_string();
+    }
+
+    /// all languages with a pack, sorted
+    pub fn get_languages(&self) -> Vec<String> {
+        let mut languages: Vec<_> = self.packs.keys().cloned().collect();
+        languages.sort();
+        languages
+    }
+}
+
+#[test]
+fn builtin_packs_are_complete() {
+    let english = I18n::builtin(&ENGLISH);
+    let schinese = I18n::builtin(&SCHINESE);
+    for key in english.keys() {
+        assert!(schinese.contains_key(key), "schinese is missing {key}");
+    }
+    assert_eq!(english.len(), schinese.len());
+}
@@ -0,0 +1,265 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use image::{imageops, Rgba, RgbaImage};
+
+use crate::{achievement::AppAchievement, setting::Setting};
+
+/// size of the icons in the table
+pub const TABLE_SIZE: u32 = 40;
+/// size of the icons in the detail panel
+pub const DETAIL_SIZE: u32 = 128;
+
+/// brightness kept by a locked icon made from the earned one
+const GRAY_BRIGHTNESS: f32 = 0.6;
+/// an icon that can not be loaded is tried again after this, it may be downloaded meanwhile
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// tile colors of placeholder icons, picked by the achievement id
+const PLACEHOLDER_COLORS: [[u8; 3]; 8] = [
+    [192, 57, 43],
+    [211, 84, 0],
+    [183, 149, 11],
+    [39, 174, 96],
+    [22, 160, 133],
+    [41, 128, 185],
+    [142, 68, 173],
+    [84, 110, 122],
+];
+
+type Key = (String, u32, bool);
+
+/// What there is to draw for an icon
+pub enum Icon {
+    /// decoded by the worker, nothing to draw yet
+    Loading,
+    Loaded(egui::TextureHandle),
+    /// the file is missing or can not be decoded
+    Missing,
+}
+
+enum Slot {
+    /// being decoded by the worker
+    Loading,
+    Loaded(egui::TextureHandle),
+    /// when it could not be loaded, so it is not tried every frame
+    Failed(Instant),
+}
+
+/// Icons decoded once and resized to the sizes they are shown at.
+///
+/// Thumbnails are kept in `{data dir}/icons/`, named after the sha1 of the icon file,
+/// so they are only decoded again when the file changes.
+/// A locked icon is made gray from the earned one when the schema has none.
+/// Decoding runs on a worker thread, the icon shows up a frame or so later.
+pub struct IconCache {
+    textures: RefCell<HashMap<Key, Slot>>,
+    requests: mpsc::Sender<(Key, egui::Context)>,
+    decoded: mpsc::Receiver<(Key, Result<RgbaImage, String>)>,
+}
+
+impl IconCache {
+    pub fn new(setting: &Setting) -> Self {
+        Self::with_dir(PathBuf::from(setting.get_data_dir()).join("icons"))
+    }
+
+    fn with_dir(dir: PathBuf) -> Self {
+        let (requests, receiver) = mpsc::channel::<(Key, egui::Context)>();
+        let (sender, decoded) = mpsc::channel();
+        // ends with the cache, when the requests are closed
+        thread::spawn(move || {
+            for ((path, size, gray), ctx) in receiver {
+                let image = thumbnail(&dir, &path, size, gray);
+                if sender.send(((path, size, gray), image)).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+        });
+        Self {
+            textures: Default::default(),
+            requests,
+            decoded,
+        }
+    }
+
+    /// texture of the icon file, once it is decoded
+    pub fn texture(&self, ctx: &egui::Context, path: &str, size: u32, gray: bool) -> Icon {
+        for (key, image) in self.decoded.try_iter() {
+            self.store(ctx, key, image);
+        }
+        let key = (path.to_string(), size, gray);
+        let mut textures = self.textures.borrow_mut();
+        match textures.get(&key) {
+            Some(Slot::Loaded(texture)) => return Icon::Loaded(texture.clone()),
+            Some(Slot::Loading) => return Icon::Loading,
+            Some(Slot::Failed(at)) if at.elapsed() < RETRY_AFTER => return Icon::Missing,
+            _ => {}
+        }
+        textures.insert(key.clone(), Slot::Loading);
+        let _ = self.requests.send((key, ctx.clone()));
+        Icon::Loading
+    }
+
+    /// make the texture of a decoded icon, on the UI thread
+    fn store(&self, ctx: &egui::Context, key: Key, image: Result<RgbaImage, String>) {
+        let slot = match image {
+            Ok(image) => {
+                let (path, size, gray) = &key;
+                let image = egui::ColorImage::from_rgba_unmultiplied(
+                    [image.width() as usize, image.height() as usize],
+                    image.as_raw(),
+                );
+                let name = format!("{}@{}{}", path, size, if *gray { "_gray" } else { "" });
+                Slot::Loaded(ctx.load_texture(name, image, egui::TextureOptions::LINEAR))
+            }
+            Err(e) => {
+                println!("Can not load icon {:?}: {}", key.0, e);
+                Slot::Failed(Instant::now())
+            }
+        };
+        self.textures.borrow_mut().insert(key, slot);
+    }
+
+    /// the earned icon, or the locked one, made from the earned one if the schema has none
+    pub fn achievement(
+        &self,
+        ctx: &egui::Context,
+        ac: &AppAchievement,
+        earned: bool,
+        size: u32,
+    ) -> Icon {
+        if earned {
+            self.texture(ctx, &ac.icon, size, false)
+        } else if !ac.icon_gray.is_empty() {
+            self.texture(ctx, &ac.icon_gray, size, false)
+        } else {
+            self.texture(ctx, &ac.icon, size, true)
+        }
+    }
+}
+
+/// the icon file fit in a square of `size`, grayed if `gray`, with the thumbnail kept in `dir`
+fn thumbnail(dir: &Path, path: &str, size: u32, gray: bool) -> Result<RgbaImage, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let name = format!(
+        "{}_{}{}.png",
+        sha1_smol::Sha1::from(&bytes).digest(),
+        size,
+        if gray { "_gray" } else { "" }
+    );
+    let cached = dir.join(name);
+    if let Ok(image) = image::open(&cached) {
+        return Ok(image.to_rgba8());
+    }
+    let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    let mut thumbnail = image
+        .resize(size, size, imageops::FilterType::Lanczos3)
+        .to_rgba8();
+    if gray {
+        thumbnail = to_gray(&thumbnail);
+    }
+    if let Err(e) = fs::create_dir_all(dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| thumbnail.save(&cached).map_err(|e| e.to_string()))
+    {
+        println!("Can not save thumbnail {:?}: {}", cached, e);
+    }
+    Ok(thumbnail)
+}
+
+/// up to two letters of the title shown on a placeholder icon
+pub fn initials(title: &str) -> String {
+    title
+        .split_whitespace()
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// tile color of the placeholder icon of the achievement id, gray for a locked one
+pub fn placeholder_color(id: &str, earned: bool) -> [u8; 3] {
+    if !earned {
+        return [90, 90, 90];
+    }
+    let hash = id
+        .bytes()
+        .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    PLACEHOLDER_COLORS[hash % PLACEHOLDER_COLORS.len()]
+}
+
+fn to_gray(image: &RgbaImage) -> RgbaImage {
+    let mut gray = RgbaImage::new(image.width(), image.height());
+    for (src, dst) in image.pixels().zip(gray.pixels_mut()) {
+        let luma = 0.299 * src[0] as f32 + 0.587 * src[1] as f32 + 0.114 * src[2] as f32;
+        let luma = (luma * GRAY_BRIGHTNESS).round() as u8;
+        *dst = Rgba([luma, luma, luma, src[3]]);
+    }
+    gray
+}
+
+#[test]
+fn cache_gray_thumbnail() {
+    let dir = crate::temp_dir::TempDir::new("icons");
+    let icon = dir.join("icon.png");
+    RgbaImage::from_pixel(128, 64, Rgba([200, 100, 0, 255]))
+        .save(&icon)
+        .unwrap();
+    let thumbnail = thumbnail(&dir.join("icons"), icon.to_str().unwrap(), 40, true).unwrap();
+    assert_eq!(thumbnail.dimensions(), (40, 20));
+    let pixel = thumbnail.get_pixel(20, 10);
+    assert!(pixel[0] == pixel[1] && pixel[1] == pixel[2]);
+    assert_eq!(fs::read_dir(dir.join("icons")).unwrap().count(), 1);
+}
+
+#[test]
+fn retry_failed_icon() {
+    let dir = crate::temp_dir::TempDir::new("retry");
+    let icon = dir.join("icon.png");
+    let path = icon.to_str().unwrap();
+    let cache = IconCache::with_dir(dir.join("icons"));
+    let ctx = egui::Context::default();
+    let decode = || {
+        let (key, image) = cache.decoded.recv_timeout(Duration::from_secs(10)).unwrap();
+        cache.store(&ctx, key, image);
+    };
+    assert!(matches!(
+        cache.texture(&ctx, path, 40, false),
+        Icon::Loading
+    ));
+    decode();
+    // not there yet, and not tried again at once
+    assert!(matches!(
+        cache.texture(&ctx, path, 40, false),
+        Icon::Missing
+    ));
+    assert!(cache
+        .decoded
+        .recv_timeout(Duration::from_millis(200))
+        .is_err());
+
+    RgbaImage::from_pixel(64, 64, Rgba([200, 100, 0, 255]))
+        .save(&icon)
+        .unwrap();
+    if let Some(Slot::Failed(at)) = cache.textures.borrow_mut().values_mut().next() {
+        *at = Instant::now().checked_sub(RETRY_AFTER).unwrap();
+    }
+    assert!(matches!(
+        cache.texture(&ctx, path, 40, false),
+        Icon::Loading
+    ));
+    decode();
+    let Icon::Loaded(texture) = cache.texture(&ctx, path, 40, false) else {
+        panic!("the icon is not loaded");
+    };
+    assert_eq!(texture.size(), [40, 40]);
+}
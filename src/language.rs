@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+/// Language codes used by the Steam API, with their native names.
+/// The order is the fallback order once the preferred languages and english are exhausted.
+pub const STEAM_LANGUAGES: [(&str, &str); 30] = [
+    ("english", "English"),
+    ("schinese", "简体中文"),
+    ("tchinese", "繁體中文"),
+    ("japanese", "日本語"),
+    ("koreana", "한국어"),
+    ("french", "Français"),
+    ("german", "Deutsch"),
+    ("spanish", "Español - España"),
+    ("latam", "Español - Latinoamérica"),
+    ("italian", "Italiano"),
+    ("portuguese", "Português"),
+    ("brazilian", "Português - Brasil"),
+    ("russian", "Русский"),
+    ("polish", "Polski"),
+    ("dutch", "Nederlands"),
+    ("danish", "Dansk"),
+    ("finnish", "Suomi"),
+    ("norwegian", "Norsk"),
+    ("swedish", "Svenska"),
+    ("czech", "Čeština"),
+    ("hungarian", "Magyar"),
+    ("romanian", "Română"),
+    ("bulgarian", "Български"),
+    ("greek", "Ελληνικά"),
+    ("turkish", "Türkçe"),
+    ("ukrainian", "Українська"),
+    ("arabic", "العربية"),
+    ("thai", "ไทย"),
+    ("vietnamese", "Tiếng Việt"),
+    ("indonesian", "Bahasa Indonesia"),
+];
+
+/// Language every lookup falls back to
+pub const FALLBACK_LANGUAGE: &str = "english";
+
+/// ISO 639-1 language -> Steam language, for locales without a regional variant
+const LOCALE_LANGUAGES: [(&str, &str); 28] = [
+    ("ar", "arabic"),
+    ("bg", "bulgarian"),
+    ("cs", "czech"),
+    ("da", "danish"),
+    ("de", "german"),
+    ("el", "greek"),
+    ("en", "english"),
+    ("fi", "finnish"),
+    ("fr", "french"),
+    ("hu", "hungarian"),
+    ("id", "indonesian"),
+    ("it", "italian"),
+    ("ja", "japanese"),
+    ("ko", "koreana"),
+    ("nb", "norwegian"),
+    ("nn", "norwegian"),
+    ("no", "norwegian"),
+    ("nl", "dutch"),
+    ("pl", "polish"),
+    ("ro", "romanian"),
+    ("ru", "russian"),
+    ("sv", "swedish"),
+    ("th", "thai"),
+    ("tr", "turkish"),
+    ("uk", "ukrainian"),
+    ("vi", "vietnamese"),
+    ("zh", "schinese"),
+    ("pt", "portuguese"),
+];
+
+/// Steam language -> BCP 47 tag, used to choose a speech voice
+const SPEECH_TAGS: [(&str, &str); 30] = [
+    ("english", "en"),
+    ("schinese", "zh-CN"),
+    ("tchinese", "zh-TW"),
+    ("japanese", "ja"),
+    ("koreana", "ko"),
+    ("french", "fr"),
+    ("german", "de"),
+    ("spanish", "es"),
+    ("latam", "es-419"),
+    ("italian", "it"),
+    ("portuguese", "pt"),
+    ("brazilian", "pt-BR"),
+    ("russian", "ru"),
+    ("polish", "pl"),
+    ("dutch", "nl"),
+    ("danish", "da"),
+    ("finnish", "fi"),
+    ("norwegian", "nb"),
+    ("swedish", "sv"),
+    ("czech", "cs"),
+    ("hungarian", "hu"),
+    ("romanian", "ro"),
+    ("bulgarian", "bg"),
+    ("greek", "el"),
+    ("turkish", "tr"),
+    ("ukrainian", "uk"),
+    ("arabic", "ar"),
+    ("thai", "th"),
+    ("vietnamese", "vi"),
+    ("indonesian", "id"),
+];
+
+/// native name of a Steam language, like '简体中文' for 'schinese'
+pub fn native_name(code: &str) -> Option<&'static str> {
+    STEAM_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+/// BCP 47 tag of a Steam language, like 'zh-CN' for 'schinese'
+pub fn speech_tag(code: &str) -> Option<&'static str> {
+    SPEECH_TAGS
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, tag)| *tag)
+}
+
+/// Pick the text of the first preferred language present in texts.
+/// Falls back to english, then to the first Steam language present,
+/// keys that are not languages (like `token`) are never used.
+pub fn pick<'a>(
+    languages: &[String],
+    texts: &'a HashMap<String, String>,
+) -> Option<(&'static str, &'a String)> {
+    languages
+        .iter()
+        .filter_map(|l| normalize(l))
+        .chain([FALLBACK_LANGUAGE])
+        .chain(STEAM_LANGUAGES.iter().map(|(l, _)| *l))
+        .find_map(|l| texts.get(l).map(|text| (l, text)))
+}
+
+/// Map an OS locale to a Steam language.
+/// Accepts forms like `zh_CN`, `zh-Hant-TW`, `pt_BR.UTF-8` or `de`.
+pub fn from_locale(locale: &str) -> Option<&'static str> {
+    let locale = locale.split(['.', '@']).next()?;
+    let mut parts = locale.split(['_', '-']);
+    let language = parts.next()?.to_lowercase();
+    let rest: Vec<String> = parts.map(|p| p.to_uppercase()).collect();
+    let has = |s: &str| rest.iter().any(|p| p == s);
+    match language.as_str() {
+        "zh" if has("HANT") || has("TW") || has("HK") || has("MO") => Some("tchinese"),
+        "pt" if has("BR") => Some("brazilian"),
+        // any region except Spain itself is Latin American Spanish
+        "es" => match rest.last() {
+            Some(region) if region != "ES" => Some("latam"),
+            _ => Some("spanish"),
+        },
+        _ => LOCALE_LANGUAGES
+            .iter()
+            .find(|(l, _)| *l == language)
+            .map(|(_, code)| *code),
+    }
+}
+
+/// Steam language code from either a Steam code or an OS locale
+pub fn normalize(language: &str) -> Option<&'static str> {
+    let lower = language.trim().to_lowercase();
+    STEAM_LANGUAGES
+        .iter()
+        .find(|(c, _)| *c == lower)
+        .map(|(c, _)| *c)
+        .or_else(|| from_locale(language.trim()))
+}
+
+/// Steam language of the current OS user
+pub fn system_language() -> Option<&'static str> {
+    sys_locale::get_locales().find_map(|locale| from_locale(&locale))
+}
+
+#[test]
+fn map_locales() {
+    assert_eq!(from_locale("zh_CN"), Some("schinese"));
+    assert_eq!(from_locale("zh-Hant-HK"), Some("tchinese"));
+    assert_eq!(from_locale("pt_BR.UTF-8"), Some("brazilian"));
+    assert_eq!(from_locale("pt_PT"), Some("portuguese"));
+    assert_eq!(from_locale("es-419"), Some("latam"));
+    assert_eq!(from_locale("es_ES"), Some("spanish"));
+    assert_eq!(from_locale("ko_KR"), Some("koreana"));
+    assert_eq!(from_locale("C"), None);
+    assert_eq!(normalize("English"), Some("english"));
+    assert_eq!(normalize("token"), None);
+}
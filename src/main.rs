@@ -1,17 +1,46 @@
 use std::sync::{Arc, Mutex};
 
 use notify::{RecursiveMode, Watcher};
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 use setting::Setting;
 
 mod achievement;
+mod audit;
+mod cli;
+mod fetch;
 mod fonts;
+mod games;
+mod guide;
+mod history;
+mod i18n;
+mod icon_cache;
+mod language;
+mod notes;
+mod notifier;
+mod rarity;
+mod schema;
+mod server;
 mod setting;
+mod showcase;
+mod sound;
+mod speech;
+mod statistics;
+#[cfg(test)]
+mod temp_dir;
+mod tray;
+mod validate;
+mod webhook;
 
 use std::sync::mpsc;
 
+use achievement::{AchievementEvent, EventKind};
 use eframe::egui;
 
 fn main() {
+    let setting = Setting::new();
+    if let Some(command) = setting.get_command() {
+        std::process::exit(cli::run(command, &setting));
+    }
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 800.0]),
         ..Default::default()
@@ -19,7 +48,7 @@ fn main() {
     eframe::run_native(
         "Steam Achievements Reminder",
         options,
-        Box::new(|cc| Ok(Box::new(MyApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(MyApp::new(cc, setting)))),
     )
     .unwrap();
     println!("Terminate successfully!");
@@ -31,34 +60,95 @@ enum AppWindow {
     Achievement,
 }
 
+/// Page of the main window
+#[derive(PartialEq, Clone, Copy)]
+enum Tab {
+    Achievements,
+    Problems,
+}
+
+/// How the achievements tab lists them
+#[derive(PartialEq, Clone, Copy)]
+enum View {
+    Table,
+    Gallery,
+}
+
 enum AppCmd {
-    AddAchievement(achievement::AppAchievement),
+    AddAchievement(Box<AchievementEvent>),
     UpdateAppAchievements(Vec<achievement::AppAchievement>),
+    /// languages the schema has any text in, sent once it is loaded
+    SchemaLanguages(Vec<&'static str>),
+    UpdateProblems(Vec<validate::Problem>),
+    Tray(tray::Action),
+    /// where the showcase is saved, or why it is not
+    ShowcaseSaved(String),
     Close,
 }
 
 struct MyApp {
     setting: Setting,
+    i18n: i18n::I18n,
 
     app: AppWindow,
+    tab: Tab,
+    view: View,
     window_pos: egui::Pos2,
     window_size: egui::Vec2,
     title_bar: f32,
     visiblilty: bool,
 
-    achievements: Vec<achievement::AppAchievement>,
+    achievements: Vec<AchievementEvent>,
     achievement: Option<achievement::AppAchievement>,
+    achievement_kind: EventKind,
     scroll_to: Option<usize>,
     time_left: f32,
     start_time: std::time::Instant,
     acheivement_align: egui::Align2,
 
-    sfx: SoundEffects,
+    sfx: sound::SoundEffects,
+    speaker: speech::Speaker,
+    notifiers: notifier::Notifiers,
+    history: history::History,
+    /// every event of the history file, oldest first
+    history_entries: Vec<history::Entry>,
+    icons: icon_cache::IconCache,
+    /// where the last showcase is saved, or why it is not
+    showcase_status: Option<String>,
+    /// a showcase is being rendered
+    rendering_showcase: bool,
+    preview_event: EventKind,
 
     sender: mpsc::Sender<AppCmd>,
     receiver: mpsc::Receiver<AppCmd>,
     watcher: Option<notify::RecommendedWatcher>,
     app_achievenemt: Vec<achievement::AppAchievement>,
+    /// choices of the secondary language
+    schema_languages: Vec<&'static str>,
+    statistics: statistics::Statistics,
+    /// rows sorted by rarity, Some(true) for the rarest first
+    rarity_sort: Option<bool>,
+    problems: Vec<validate::Problem>,
+    notes: notes::Notes,
+    filter: notes::Filter,
+    /// the achievement whose note is edited, its draft and the tag being typed
+    note_editor: Option<(String, notes::Note, String)>,
+    /// where the last export is saved, or why it is not
+    export_status: Option<String>,
+    guide: guide::Guide,
+    /// id of the achievement shown in the detail panel
+    selected: Option<String>,
+    tray: Option<tray::Tray>,
+    /// popup, mute and UI language the tray menu is built with
+    tray_state: (bool, bool, String),
+    /// set on quit, so closing the window is not turned into hiding it to the tray
+    quitting: bool,
+    /// the window can be hidden, not on Wayland where it is shrunk instead
+    can_hide: bool,
+    /// games to switch to from the tray
+    games: Vec<games::Game>,
+    /// go to the hidden reminder once the monitor size is known
+    start_in_tray: bool,
     #[allow(unused)]
     send_app_achievenemt: Arc<Mutex<bool>>,
 }
@@ -66,34 +156,81 @@ struct MyApp {
 impl eframe::App for MyApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.request_repaint_after_secs(1.0);
+        self.sfx.poll();
         // println!("12345");
         while let Ok(recv) = self.receiver.try_recv() {
             match recv {
-                AppCmd::AddAchievement(achievement) => self.add_achievement(ctx, achievement),
+                AppCmd::AddAchievement(event) => self.add_achievement(ctx, *event),
                 AppCmd::Close => self.close(ctx),
                 AppCmd::UpdateAppAchievements(vec) => {
+                    if let Some(server) = self.notifiers.get_server() {
+                        server.set_achievements(vec.clone());
+                    }
+                    self.statistics = statistics::Statistics::new(&vec);
                     self.app_achievenemt = vec;
                     // *self.send_app_achievenemt.lock().unwrap() = false;
                 }
+                AppCmd::SchemaLanguages(languages) => self.schema_languages = languages,
+                AppCmd::UpdateProblems(problems) => self.problems = problems,
+                AppCmd::Tray(action) => self.tray_action(ctx, action),
+                AppCmd::ShowcaseSaved(status) => {
+                    self.showcase_status = Some(status);
+                    self.rendering_showcase = false;
+                }
+            }
+        }
+        if ctx.input(|i| i.viewport().close_requested())
+            && self.tray.is_some()
+            && self.can_hide
+            && !self.quitting
+        {
+            // the window goes to the tray, Quit of the tray menu ends the reminder
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            match self.app {
+                AppWindow::Main => self.goto_achievement_window(ctx),
+                AppWindow::Achievement => self.hide(ctx),
             }
         }
+        let tray_state = Self::tray_state(&self.setting, &self.i18n, &self.notifiers);
+        if let Some(tray) = self.tray.as_mut().filter(|_| tray_state != self.tray_state) {
+            let menu = Self::tray_menu(
+                &self.setting,
+                &self.i18n,
+                &self.notifiers,
+                self.games.clone(),
+            );
+            tray.update(menu);
+            self.tray_state = tray_state;
+        }
+        if self.start_in_tray && ctx.input(|i| i.viewport().monitor_size.is_some()) {
+            self.start_in_tray = false;
+            self.goto_achievement_window(ctx);
+        }
         match self.app {
             AppWindow::Main => {
                 self.main_window(ctx);
                 if self.visiblilty && self.get_pop_up_window() {
-                    egui::Window::new("Achievement Window")
+                    egui::Window::new(self.i18n.tr("popup_window"))
+                        .id(egui::Id::new("Achievement Window"))
                         // .anchor(egui::Align2::RIGHT_BOTTOM, [-0.5, -0.5])
                         .movable(true)
                         .show(ctx, |ui| {
                             if let Some(ac) = &self.achievement {
-                                if ui.label(format!(
-                                    "Achievement {}!\nId: \t{:?}\nTitle: \t{:?}\nDescrition: \t{:?}\n --- Click to jump ---",
-                                    if ac.state {"Get"}else{"Lose"},
-                                    ac.id,
-                                    ac.title,
-                                    ac.description,
-                                )).clicked() {
-                                    self.scroll_to = self.app_achievenemt.iter().position(|a|a.id == ac.id);
+                                let state = if ac.state {
+                                    self.i18n.tr("popup_state_get")
+                                } else {
+                                    self.i18n.tr("popup_state_lose")
+                                };
+                                let summary = self
+                                    .i18n
+                                    .tr("popup_summary")
+                                    .replace("{state}", state)
+                                    .replace("{id}", &format!("{:?}", ac.id))
+                                    .replace("{title}", &format!("{:?}", ac.title))
+                                    .replace("{description}", &format!("{:?}", ac.description));
+                                if ui.label(summary).clicked() {
+                                    self.scroll_to =
+                                        self.app_achievenemt.iter().position(|a| a.id == ac.id);
                                 }
                             }
                         });
@@ -118,16 +255,45 @@ impl eframe::App for MyApp {
 
 impl MyApp {
     // const ACHIEVEMENT_WINDOW_SIZE: (f32, f32) = (500.0, 150.0);
-    fn new(cc: &eframe::CreationContext) -> Self {
+    fn new(cc: &eframe::CreationContext, setting: Setting) -> Self {
         egui_extras::install_image_loaders(&cc.egui_ctx);
         // MyApp::load_fonts(&cc.egui_ctx);
-        let setting = setting::Setting::new();
         setting.print_all_info();
         fonts::load_system_font(&cc.egui_ctx, &setting);
+        let i18n = i18n::I18n::new(&setting);
+        let sfx = sound::SoundEffects::new(&setting);
+        let speaker = speech::Speaker::new(&setting);
+        let notifiers = notifier::Notifiers::new(&setting);
+        let history = history::History::new(&setting);
+        let history_entries = history.load();
+        let icons = icon_cache::IconCache::new(&setting);
+        let notes = notes::Notes::new(&setting);
+        let guide = guide::Guide::new(&setting);
+        games::Game::current(&setting).remember(&setting);
+        let games = games::known(&setting);
         let (sender, receiver) = mpsc::channel();
         let send_app_achievenemt = Arc::new(Mutex::new(true));
         let watcher =
             Self::file_monitor_start(sender.clone(), Arc::clone(&send_app_achievenemt), &setting);
+        let tray = if setting.get_tray() {
+            let menu = Self::tray_menu(&setting, &i18n, &notifiers, games.clone());
+            let (tray_sender, ctx) = (sender.clone(), cc.egui_ctx.clone());
+            tray::Tray::new(
+                menu,
+                Arc::new(move |action| {
+                    let _ = tray_sender.send(AppCmd::Tray(action));
+                    ctx.request_repaint();
+                }),
+            )
+        } else {
+            None
+        };
+        let start_in_tray = setting.get_start_in_tray();
+        let tray_state = Self::tray_state(&setting, &i18n, &notifiers);
+        let can_hide = !matches!(
+            cc.window_handle().map(|handle| handle.as_raw()),
+            Ok(RawWindowHandle::Wayland(_))
+        );
 
         cc.egui_ctx.set_visuals(if setting.get_dark_mode() {
             egui::Visuals::dark()
@@ -136,22 +302,50 @@ impl MyApp {
         });
         Self {
             setting,
+            i18n,
             app: AppWindow::Main,
+            tab: Tab::Achievements,
+            view: View::Table,
             window_pos: [0.0, 0.0].into(),
             window_size: [600.0, 400.0].into(),
             title_bar: 50.0,
             visiblilty: true,
             achievements: vec![],
             achievement: None,
+            achievement_kind: EventKind::Unlock,
             scroll_to: None,
             time_left: 0.0,
             start_time: std::time::Instant::now(),
             acheivement_align: egui::Align2::RIGHT_BOTTOM,
-            sfx: SoundEffects::new(),
+            sfx,
+            speaker,
+            notifiers,
+            history,
+            history_entries,
+            icons,
+            showcase_status: None,
+            rendering_showcase: false,
+            preview_event: EventKind::Unlock,
             sender,
             receiver,
             watcher,
             app_achievenemt: vec![],
+            schema_languages: vec![],
+            statistics: Default::default(),
+            rarity_sort: None,
+            problems: vec![],
+            notes,
+            filter: Default::default(),
+            note_editor: None,
+            export_status: None,
+            guide,
+            selected: None,
+            tray,
+            tray_state,
+            quitting: false,
+            can_hide,
+            games,
+            start_in_tray,
             send_app_achievenemt,
         }
     }
@@ -161,9 +355,24 @@ impl MyApp {
         send_app_achievenemt: Arc<Mutex<bool>>,
         setting: &Setting,
     ) -> Option<notify::RecommendedWatcher> {
-        let achievements_raw: achievement::AchievementsRaw =
-            achievement::AchievementsRaw::new(setting);
+        let achievements_raw = match achievement::AchievementsRaw::new(setting) {
+            Ok(achievements_raw) => achievements_raw,
+            Err(problem) => {
+                println!("{}", problem);
+                sender.send(AppCmd::UpdateProblems(vec![problem])).unwrap();
+                return None;
+            }
+        };
+        sender
+            .send(AppCmd::SchemaLanguages(
+                achievements_raw.get_schema_languages(),
+            ))
+            .unwrap();
         let mut achievements = achievement::Achievements::new(setting);
+        let mut problems = validate::validate(&achievements_raw, &achievements);
+        sender
+            .send(AppCmd::UpdateProblems(problems.clone()))
+            .unwrap();
 
         sender
             .send(AppCmd::UpdateAppAchievements(
@@ -178,43 +387,74 @@ impl MyApp {
         let mut watcher = notify::recommended_watcher(move |res| match res {
             Ok(_) => {
                 let mut is_updated = false;
+                let mut earned = achievements_raw.count_earned(&achievements);
+                let total = achievements_raw.achievements.len();
                 if let Some(updated) = achievements.update() {
-                    let send_msg = |name: String, state: bool| {
+                    let send_msg = |name: String, state: bool, kind: EventKind, earned: usize| {
                         if let Some(achievement) = achievements_raw.get(&name) {
-                            let icon = if state {
-                                achievements_raw.get_icon(achievement)
-                            } else {
-                                achievements_raw.get_icon_gray(achievement)
-                            };
+                            let icon = achievements_raw.get_icon(achievement);
                             let ac = achievement::AppAchievement {
                                 id: name.clone(),
                                 icon: icon.as_os_str().to_str().unwrap().to_string(),
-                                state: state,
+                                icon_gray: achievements_raw.get_icon_gray_string(achievement),
+                                state,
                                 date: achievements.get_time(&name).unwrap(),
+                                earned_time: achievements.achievements[&name].earned_time,
                                 title: achievements_raw.get_display_name(achievement),
                                 description: achievements_raw.get_description(achievement),
                                 visibility: achievement.hidden == "0",
+                                missing_languages: achievements_raw
+                                    .get_missing_languages(achievement),
+                                rarity: achievements_raw.get_rarity(&name),
+                                raw: achievement.clone(),
                             };
                             println!(
                                 "Achievement {:?}: {:#?}",
                                 if state { "get" } else { "lose" },
-                                (&ac.title, &ac.description, &ac.date, &ac.icon)
+                                (&ac.title, &ac.description, &ac.date, ac.get_state_icon())
                             );
-                            sender.send(AppCmd::AddAchievement(ac)).unwrap();
+                            sender
+                                .send(AppCmd::AddAchievement(Box::new(AchievementEvent {
+                                    kind,
+                                    achievement: ac,
+                                    earned,
+                                    total,
+                                })))
+                                .unwrap();
                             println!("File Updated!");
+                        } else {
+                            println!("Achievement {:?} is not in the schema, it is ignored", name);
                         }
                     };
                     // get achievement
                     for name in updated.0 {
-                        send_msg(name, true);
+                        if achievements_raw.get(&name).is_some() {
+                            earned += 1;
+                        }
+                        let kind = EventKind::for_unlock(
+                            earned,
+                            total,
+                            achievements_raw.get_rarity(&name),
+                        );
+                        send_msg(name, true, kind, earned);
                         is_updated = true;
                     }
                     // lose achievement
                     for name in updated.1 {
-                        send_msg(name, false);
+                        if achievements_raw.get(&name).is_some() {
+                            earned = earned.saturating_sub(1);
+                        }
+                        send_msg(name, false, EventKind::Relock, earned);
                         is_updated = true;
                     }
                 }
+                let updated_problems = validate::validate(&achievements_raw, &achievements);
+                if updated_problems != problems {
+                    problems = updated_problems;
+                    sender
+                        .send(AppCmd::UpdateProblems(problems.clone()))
+                        .unwrap();
+                }
                 if is_updated && *send_app_achievenemt.lock().unwrap() {
                     sender
                         .send(AppCmd::UpdateAppAchievements(
@@ -241,14 +481,14 @@ impl MyApp {
                 ui.allocate_space([10.0, 10.0].into());
                 ui.horizontal(|ui| {
                     ui.allocate_space([10.0, 10.0].into());
-                    let btn_run = egui::RichText::new("⬤ Run Reminder!")
+                    let btn_run = egui::RichText::new(self.i18n.tr("run_reminder"))
                         .color(egui::Color32::DARK_GREEN)
                         .size(30.0);
                     if ui.button(btn_run).clicked() {
-                        self.to_achievement_window(ctx);
+                        self.goto_achievement_window(ctx);
                     }
                     ui.allocate_space([20.0, 10.0].into());
-                    let btn_exit = egui::RichText::new("⬤ Close!")
+                    let btn_exit = egui::RichText::new(self.i18n.tr("close"))
                         .color(egui::Color32::RED)
                         .size(30.0);
                     if ui.button(btn_exit).clicked() {
@@ -256,7 +496,7 @@ impl MyApp {
                     }
                     ui.allocate_space([20.0, 10.0].into());
                     if self.setting.get_dark_mode() {
-                        let btn_exit = egui::RichText::new("Go Light Mode!")
+                        let btn_exit = egui::RichText::new(self.i18n.tr("light_mode"))
                             .color(egui::Color32::WHITE)
                             .size(30.0);
                         if ui.button(btn_exit).clicked() {
@@ -264,7 +504,7 @@ impl MyApp {
                             ctx.set_visuals(egui::Visuals::light());
                         }
                     } else {
-                        let btn_exit = egui::RichText::new("Go Dark Mode!")
+                        let btn_exit = egui::RichText::new(self.i18n.tr("dark_mode"))
                             .color(egui::Color32::BLACK)
                             .size(30.0);
                         if ui.button(btn_exit).clicked() {
@@ -272,32 +512,579 @@ impl MyApp {
                             ctx.set_visuals(egui::Visuals::dark());
                         }
                     }
+                    ui.allocate_space([20.0, 10.0].into());
+                    self.language_selector(ui);
+                    ui.allocate_space([20.0, 10.0].into());
+                    self.secondary_language_selector(ui);
+                });
+                ui.horizontal(|ui| {
+                    ui.allocate_space([10.0, 10.0].into());
+                    self.sound_controls(ui);
                 });
             });
+        self.detail_panel(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.draw_table(ui);
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut self.tab,
+                    Tab::Achievements,
+                    self.i18n.tr("tab_achievements"),
+                );
+                let errors = self.problems.iter().filter(|p| p.is_error()).count();
+                let mut problems = egui::RichText::new(format!(
+                    "{} ({})",
+                    self.i18n.tr("tab_problems"),
+                    self.problems.len()
+                ));
+                if errors > 0 {
+                    problems = problems.color(egui::Color32::RED);
+                }
+                ui.selectable_value(&mut self.tab, Tab::Problems, problems);
+            });
+            ui.separator();
+            match self.tab {
+                Tab::Achievements => {
+                    self.statistics_panel(ui);
+                    self.filter_bar(ui);
+                    match self.view {
+                        View::Table => self.draw_table(ui),
+                        View::Gallery => self.draw_gallery(ui),
+                    }
+                }
+                Tab::Problems => self.problems_panel(ui),
+            }
         });
+        self.note_editor_window(ctx);
     }
 
-    fn to_achievement_window(&mut self, ctx: &egui::Context) {
+    /// guide and full information of the achievement clicked in the table
+    fn detail_panel(&mut self, ctx: &egui::Context) {
+        if self.selected.is_some() {
+            self.guide.reload();
+        }
+        let Some(ac) = self
+            .selected
+            .as_ref()
+            .and_then(|id| self.app_achievenemt.iter().find(|ac| ac.id == *id))
+        else {
+            return;
+        };
+        let mut close = false;
+        egui::SidePanel::right("achievement_detail")
+            .resizable(true)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("✖").clicked() {
+                        close = true;
+                    }
+                    ui.heading(&ac.title);
+                });
+                ui.label(egui::RichText::new(&ac.id).weak());
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for (earned, label) in [(true, "earned_icon"), (false, "locked_icon")] {
+                            ui.vertical(|ui| {
+                                self.achievement_icon(
+                                    ui,
+                                    ac,
+                                    earned,
+                                    icon_cache::DETAIL_SIZE as f32,
+                                    8.0,
+                                );
+                                ui.label(egui::RichText::new(self.i18n.tr(label)).weak());
+                            });
+                        }
+                    });
+                    egui::Grid::new("detail_info").show(ui, |ui| {
+                        ui.label(self.i18n.tr("col_state"));
+                        ui.label(if ac.state {
+                            self.i18n.tr("achieved")
+                        } else {
+                            "-"
+                        });
+                        ui.end_row();
+                        ui.label(self.i18n.tr("earned_time"));
+                        ui.monospace(ac.earned_time.to_string());
+                        ui.end_row();
+                        ui.label(self.i18n.tr("col_date"));
+                        ui.label(if ac.earned_time > 0 {
+                            statistics::format_time(ac.earned_time)
+                        } else {
+                            "-".to_string()
+                        });
+                        ui.end_row();
+                        ui.label(self.i18n.tr("hidden"));
+                        ui.label(format!("{} ({:?})", !ac.visibility, ac.raw.hidden));
+                        ui.end_row();
+                        ui.label(self.i18n.tr("col_rarity"));
+                        ui.label(ac.rarity.map_or("-".to_string(), |r| format!("{:.1}%", r)));
+                        ui.end_row();
+                    });
+                    egui::CollapsingHeader::new(self.i18n.tr("guide"))
+                        .default_open(true)
+                        .show(ui, |ui| match self.guide.get(ac) {
+                            Some(markdown) => {
+                                let dir = self.guide.get_path().parent().unwrap();
+                                guide::show(ui, markdown, dir);
+                            }
+                            None => {
+                                ui.label(
+                                    egui::RichText::new(
+                                        self.i18n.tr("no_guide").replace("{id}", &ac.id).replace(
+                                            "{path}",
+                                            &self.guide.get_path().display().to_string(),
+                                        ),
+                                    )
+                                    .weak(),
+                                );
+                            }
+                        });
+                    egui::CollapsingHeader::new(self.i18n.tr("all_languages"))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            let mut languages: Vec<&String> = ac
+                                .raw
+                                .displayName
+                                .keys()
+                                .chain(ac.raw.description.keys())
+                                .collect();
+                            languages.sort();
+                            languages.dedup();
+                            egui::Grid::new("detail_languages")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for language in languages {
+                                        ui.label(egui::RichText::new(language).weak());
+                                        ui.vertical(|ui| {
+                                            if let Some(title) = ac.raw.displayName.get(language) {
+                                                ui.strong(title);
+                                            }
+                                            if let Some(description) =
+                                                ac.raw.description.get(language)
+                                            {
+                                                ui.label(description);
+                                            }
+                                        });
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    egui::CollapsingHeader::new(self.i18n.tr("history"))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            let entries: Vec<&history::Entry> = self
+                                .history_entries
+                                .iter()
+                                .rev()
+                                .filter(|entry| entry.id == ac.id)
+                                .collect();
+                            if entries.is_empty() {
+                                ui.label(egui::RichText::new(self.i18n.tr("no_history")).weak());
+                            }
+                            egui::Grid::new("detail_history")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for entry in entries {
+                                        ui.label(statistics::format_time(entry.time));
+                                        ui.label(self.i18n.tr(&entry.event));
+                                        ui.label(format!("{}/{}", entry.earned, entry.total));
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    egui::CollapsingHeader::new(self.i18n.tr("icon_paths")).show(ui, |ui| {
+                        ui.label(format!("icon: {}", ac.icon));
+                        ui.label(format!("icon_gray: {}", ac.icon_gray));
+                    });
+                    egui::CollapsingHeader::new(self.i18n.tr("raw_json")).show(ui, |ui| {
+                        let json = serde_json::to_string_pretty(&ac.raw).unwrap();
+                        ui.add(
+                            egui::TextEdit::multiline(&mut json.as_str())
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                });
+            });
+        if close {
+            self.selected = None;
+        }
+    }
+
+    /// search, tag and checklist filters of the achievements, and the export
+    fn filter_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.view, View::Table, self.i18n.tr("view_table"));
+            ui.selectable_value(&mut self.view, View::Gallery, self.i18n.tr("view_gallery"));
+            if self.view == View::Gallery {
+                let mut size = self.setting.get_gallery_tile_size();
+                let slider = egui::Slider::new(&mut size, 32.0..=128.0)
+                    // every size is a thumbnail file in the cache
+                    .step_by(16.0)
+                    .text(self.i18n.tr("tile_size"));
+                if ui.add(slider).changed() {
+                    self.setting.set_gallery_tile_size(size);
+                }
+            }
+            ui.separator();
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter.text)
+                    .hint_text(self.i18n.tr("search"))
+                    .desired_width(200.0),
+            );
+            let all_tags = self.i18n.tr("all_tags").to_string();
+            egui::ComboBox::from_id_salt("tag_filter")
+                .selected_text(self.filter.tag.clone().unwrap_or(all_tags.clone()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter.tag, None, all_tags);
+                    for tag in self.notes.get_tags() {
+                        ui.selectable_value(&mut self.filter.tag, Some(tag.clone()), tag);
+                    }
+                });
+            ui.checkbox(&mut self.filter.planned_only, self.i18n.tr("planned_only"));
+            ui.separator();
+            if ui.button(self.i18n.tr("export")).clicked() {
+                let path =
+                    std::path::PathBuf::from(self.setting.get_data_dir()).join("achievements.csv");
+                self.export_status = Some(
+                    match notes::export(&self.app_achievenemt, &self.notes, &path) {
+                        Ok(()) => format!("{} {}", self.i18n.tr("saved_to"), path.display()),
+                        Err(e) => format!("⚠ {}", e),
+                    },
+                );
+            }
+            if let Some(status) = &self.export_status {
+                ui.label(egui::RichText::new(status).weak());
+            }
+        });
+    }
+
+    /// notes of one achievement, saved when the window is confirmed
+    fn note_editor_window(&mut self, ctx: &egui::Context) {
+        let Some((id, note, new_tag)) = &mut self.note_editor else {
+            return;
+        };
+        let mut done = None;
+        egui::Window::new(format!("{} {}", self.i18n.tr("edit_note"), id))
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut note.planned, self.i18n.tr("planned"));
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(self.i18n.tr("tags"));
+                    // the known tags, then the new ones of the draft
+                    let mut tags = self.notes.get_tags();
+                    for tag in &note.tags {
+                        if !tags.contains(tag) {
+                            tags.push(tag.clone());
+                        }
+                    }
+                    for tag in tags {
+                        let mut on = note.tags.contains(&tag);
+                        if ui.toggle_value(&mut on, &tag).changed() {
+                            if on {
+                                note.tags.push(tag);
+                            } else {
+                                note.tags.retain(|t| *t != tag);
+                            }
+                        }
+                    }
+                    let input = ui.add(
+                        egui::TextEdit::singleline(new_tag)
+                            .hint_text("+")
+                            .desired_width(80.0),
+                    );
+                    if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let tag = new_tag.trim().to_lowercase();
+                        if !tag.is_empty() && !note.tags.contains(&tag) {
+                            note.tags.push(tag);
+                        }
+                        new_tag.clear();
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::multiline(&mut note.text)
+                        .desired_rows(5)
+                        .desired_width(f32::INFINITY),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button(self.i18n.tr("save")).clicked() {
+                        done = Some(true);
+                    }
+                    if ui.button(self.i18n.tr("cancel")).clicked() {
+                        done = Some(false);
+                    }
+                });
+            });
+        match done {
+            Some(true) => {
+                let (id, note, _) = self.note_editor.take().unwrap();
+                self.notes.set(&id, note);
+            }
+            Some(false) => self.note_editor = None,
+            None => {}
+        }
+    }
+
+    /// what is wrong with the schema and the save file, a click shows the achievement
+    fn problems_panel(&mut self, ui: &mut egui::Ui) {
+        if self.problems.is_empty() {
+            ui.label(self.i18n.tr("no_problems"));
+            return;
+        }
+        let mut selected = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for problem in &self.problems {
+                ui.horizontal(|ui| {
+                    let (level, color) = if problem.is_error() {
+                        ("error", egui::Color32::RED)
+                    } else {
+                        ("warning", egui::Color32::from_rgb(255, 165, 0))
+                    };
+                    ui.label(egui::RichText::new(self.i18n.tr(level)).color(color));
+                    if ui.link(problem.to_string()).clicked() {
+                        selected = Some(problem.id().to_string());
+                    }
+                });
+            }
+        });
+        if let Some(id) = selected {
+            if let Some(index) = self.app_achievenemt.iter().position(|a| a.id == id) {
+                self.scroll_to = Some(index);
+                self.tab = Tab::Achievements;
+            }
+        }
+    }
+
+    fn statistics_panel(&mut self, ui: &mut egui::Ui) {
+        let stats = &self.statistics;
+        let mut save_showcase = false;
+        let time = |t: Option<u64>| t.map(statistics::format_time).unwrap_or("-".to_string());
+        egui::CollapsingHeader::new(self.i18n.tr("statistics"))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    egui::Grid::new("Statistics")
+                        .num_columns(2)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label(self.i18n.tr("stat_completion"));
+                            ui.strong(format!(
+                                "{}/{} ({:.1}%)",
+                                stats.earned,
+                                stats.total,
+                                stats.completion()
+                            ));
+                            ui.end_row();
+                            ui.label(self.i18n.tr("stat_hidden"));
+                            ui.label(format!("{}/{}", stats.hidden_earned, stats.hidden_total));
+                            ui.end_row();
+                            ui.label(self.i18n.tr("stat_first"));
+                            ui.label(time(stats.first));
+                            ui.end_row();
+                            ui.label(self.i18n.tr("stat_latest"));
+                            ui.label(time(stats.latest));
+                            ui.end_row();
+                            ui.label(self.i18n.tr("stat_longest_gap"));
+                            ui.label(match stats.longest_gap {
+                                Some((from, to)) => format!(
+                                    "{} ({} ~ {})",
+                                    statistics::format_duration(to - from),
+                                    statistics::format_time(from),
+                                    statistics::format_time(to)
+                                ),
+                                None => "-".to_string(),
+                            });
+                            ui.end_row();
+                            ui.label(self.i18n.tr("stat_average_gap"));
+                            ui.label(
+                                stats
+                                    .average_gap
+                                    .map(statistics::format_duration)
+                                    .unwrap_or("-".to_string()),
+                            );
+                            ui.end_row();
+                        });
+                    ui.allocate_space([20.0, 10.0].into());
+                    ui.vertical(|ui| {
+                        if stats.total > 0 && stats.earned == stats.total {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(self.i18n.tr("perfect_game"))
+                                        .strong()
+                                        .color(egui::Color32::GOLD),
+                                );
+                                if ui.button(self.i18n.tr("copy_summary")).clicked() {
+                                    ui.ctx().copy_text(self.perfect_game_summary(stats));
+                                }
+                            });
+                        }
+                        ui.horizontal(|ui| {
+                            let button = egui::Button::new(self.i18n.tr("save_showcase"));
+                            if ui.add_enabled(!self.rendering_showcase, button).clicked() {
+                                save_showcase = true;
+                            }
+                            if let Some(status) = &self.showcase_status {
+                                ui.label(status);
+                            }
+                        });
+                        ui.label(self.i18n.tr("stat_per_day"));
+                        self.unlock_histogram(ui);
+                    });
+                });
+            });
+        ui.separator();
+        if save_showcase {
+            self.save_showcase();
+        }
+    }
+
+    /// one bar per day, the hovered one tells its date and count
+    fn unlock_histogram(&self, ui: &mut egui::Ui) {
+        let per_day = &self.statistics.per_day;
+        let size = egui::vec2(ui.available_width().max(120.0), 90.0);
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+        if per_day.is_empty() {
+            return;
+        }
+        let max = per_day.iter().map(|(_, n)| *n).max().unwrap_or(1).max(1);
+        let width = rect.width() / per_day.len() as f32;
+        let hovered = response
+            .hover_pos()
+            .map(|pos| ((pos.x - rect.left()) / width) as usize)
+            .filter(|i| *i < per_day.len());
+        for (i, (_, count)) in per_day.iter().enumerate() {
+            let height = (rect.height() - 4.0) * *count as f32 / max as f32;
+            let left = rect.left() + i as f32 * width;
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left, rect.bottom() - height),
+                egui::pos2(left + (width - 1.0).max(1.0), rect.bottom()),
+            );
+            let color = if hovered == Some(i) {
+                ui.visuals().hyperlink_color
+            } else {
+                ui.visuals().selection.bg_fill
+            };
+            painter.rect_filled(bar, 0.0, color);
+        }
+        if let Some((day, count)) = hovered.map(|i| per_day[i]) {
+            response.on_hover_text(format!("{}: {}", day, count));
+        }
+    }
+
+    fn language_selector(&mut self, ui: &mut egui::Ui) {
+        let mut selected = self.i18n.get_language().to_string();
+        egui::ComboBox::from_label(self.i18n.tr("ui_language"))
+            .selected_text(language::native_name(&selected).unwrap_or(&selected))
+            .show_ui(ui, |ui| {
+                for code in self.i18n.get_languages() {
+                    let name = language::native_name(&code).unwrap_or(&code);
+                    ui.selectable_value(&mut selected, code.clone(), name);
+                }
+            });
+        if selected != self.i18n.get_language() {
+            self.i18n.set_language(&selected);
+            self.setting.set_ui_language(&selected);
+        }
+    }
+
+    /// choose the second language among those present in the loaded schema
+    fn secondary_language_selector(&mut self, ui: &mut egui::Ui) {
+        let mut selected = self.setting.get_secondary_language();
+        let name = |code: &Option<String>| match code {
+            Some(code) => language::native_name(code).unwrap_or(code).to_string(),
+            None => self.i18n.tr("none").to_string(),
+        };
+        egui::ComboBox::from_label(self.i18n.tr("secondary_language"))
+            .selected_text(name(&selected))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, name(&None));
+                for code in &self.schema_languages {
+                    let code = Some(code.to_string());
+                    ui.selectable_value(&mut selected, code.clone(), name(&code));
+                }
+            });
+        if selected != self.setting.get_secondary_language() {
+            self.setting.set_secondary_language(selected.as_deref());
+        }
+    }
+
+    fn sound_controls(&mut self, ui: &mut egui::Ui) {
+        let mut mute = self.setting.get_mute();
+        if ui.checkbox(&mut mute, self.i18n.tr("mute")).changed() {
+            self.setting.set_mute(mute);
+            self.sfx.set_mute(mute);
+        }
+        let mut volume = self.setting.get_volume();
+        let slider = egui::Slider::new(&mut volume, 0.0..=2.0).text(self.i18n.tr("volume"));
+        if ui.add_enabled(!mute, slider).changed() {
+            self.setting.set_volume(volume);
+            self.sfx.set_volume(volume);
+        }
+        ui.allocate_space([20.0, 10.0].into());
+        egui::ComboBox::from_id_salt("Preview Sound")
+            .selected_text(self.i18n.tr(self.preview_event.name()))
+            .show_ui(ui, |ui| {
+                for event in EventKind::ALL {
+                    ui.selectable_value(&mut self.preview_event, event, self.i18n.tr(event.name()));
+                }
+            });
+        if ui.button(self.i18n.tr("preview")).clicked() {
+            self.sfx.preview(self.preview_event);
+        }
+        ui.allocate_space([20.0, 10.0].into());
+        let mut device = self.sfx.get_device().cloned();
+        egui::ComboBox::from_label(self.i18n.tr("audio_device"))
+            .selected_text(device.as_deref().unwrap_or(self.i18n.tr("default")))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut device, None, self.i18n.tr("default"));
+                for name in self.sfx.get_devices() {
+                    ui.selectable_value(&mut device, Some(name.clone()), name);
+                }
+            });
+        if device.as_ref() != self.sfx.get_device() {
+            self.setting.set_audio_device(device.clone());
+            self.sfx.set_device(device);
+        }
+        ui.allocate_space([20.0, 10.0].into());
+        let mut tts = self.setting.get_tts();
+        if ui.checkbox(&mut tts, self.i18n.tr("speak")).changed() {
+            self.setting.set_tts(tts);
+            self.speaker.set_enabled(tts);
+        }
+        if let Some(webhook) = self.notifiers.get_webhook() {
+            ui.allocate_space([20.0, 10.0].into());
+            if ui.button(self.i18n.tr("send_test")).clicked() {
+                webhook.send_test(self.i18n.tr("popup_gained"));
+            }
+        }
+        if let Some(warning) = self.sfx.get_warning() {
+            ui.label(egui::RichText::new(format!("⚠ {}", warning)).color(egui::Color32::ORANGE));
+        }
+    }
+
+    fn goto_achievement_window(&mut self, ctx: &egui::Context) {
         println!("--- Convert to achievement window! ---");
         self.app = AppWindow::Achievement;
         println!("moniter_size: {:#?}", ctx.input(|i| i.viewport().clone()));
         let moniter_size = ctx.input(|i| i.viewport().monitor_size.unwrap());
         let window_pos = ctx
             .input(|i| i.viewport().outer_rect)
-            .and_then(|r| Some(r.min))
+            .map(|r| r.min)
             .unwrap_or(egui::Pos2 { x: 100.0, y: 100.0 });
         let window_size = ctx
             .input(|i| i.viewport().inner_rect)
-            .and_then(|r| Some(r.size()))
+            .map(|r| r.size())
             .unwrap_or(egui::Vec2 {
                 x: 1160.0,
                 y: 800.0,
             });
         let title_bar = ctx
             .input(|i| i.viewport().outer_rect)
-            .and_then(|r| Some(r.height()))
+            .map(|r| r.height())
             .unwrap_or(1200.0)
             - window_size.y;
         let new_pos = moniter_size - self.setting.get_achievement_window_size().into();
@@ -344,20 +1131,36 @@ impl MyApp {
     }
 
     fn achievement_window(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
+        let mut frame = egui::Frame::central_panel(&ctx.style());
+        let perfect = self.achievement_kind == EventKind::Completion;
+        if perfect {
+            frame = frame.stroke(egui::Stroke::new(6.0, egui::Color32::GOLD));
+        } else if let Some(color) = self
+            .achievement
+            .as_ref()
+            .and_then(|ac| ac.rarity)
+            .and_then(|percent| tier_color(rarity::Tier::of(percent)))
+        {
+            frame = frame.stroke(egui::Stroke::new(4.0, color));
+        }
+        egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
             let height = ui.max_rect().height();
+            let secondary = self.setting.get_secondary_language();
             ui.horizontal(|ui| {
                 let ac = self.achievement.as_ref().unwrap();
-                ui.add(
-                    egui::Image::new(&format!("file://{}", ac.icon))
-                        .fit_to_exact_size([height, height].into())
-                        .rounding(height / 10.0),
-                );
+                self.achievement_icon(ui, ac, ac.state, height, height / 10.0);
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     ui.vertical_centered(|ui| {
-                        if ac.state {
+                        if perfect {
+                            ui.label(
+                                egui::RichText::new(self.i18n.tr("perfect_game"))
+                                    .size(24.0)
+                                    .strong()
+                                    .color(egui::Color32::GOLD),
+                            );
+                        } else if ac.state {
                             ui.label(
-                                egui::RichText::new("Achievement Gained! CONGRATS!")
+                                egui::RichText::new(self.i18n.tr("popup_gained"))
                                     .size(18.0)
                                     .color(if self.setting.get_dark_mode() {
                                         egui::Color32::LIGHT_GREEN
@@ -367,7 +1170,7 @@ impl MyApp {
                             );
                         } else {
                             ui.label(
-                                egui::RichText::new("Achievement Seems Disappeared!")
+                                egui::RichText::new(self.i18n.tr("popup_lost"))
                                     .size(18.0)
                                     .color(egui::Color32::ORANGE),
                             );
@@ -383,11 +1186,19 @@ impl MyApp {
                                     egui::Color32::DARK_BLUE
                                 }),
                         );
+                        if let Some(title) = secondary.as_deref().and_then(|l| ac.get_title_in(l)) {
+                            ui.label(egui::RichText::new(title).size(14.0).weak());
+                        }
                         ui.label(
                             egui::RichText::new(&ac.description)
                                 .size(14.0)
                                 .color(egui::Color32::GRAY),
                         );
+                        if let Some(description) =
+                            secondary.as_deref().and_then(|l| ac.get_description_in(l))
+                        {
+                            ui.label(egui::RichText::new(description).size(12.0).weak());
+                        }
                         ui.separator();
                         ui.label(
                             egui::RichText::new(&ac.date)
@@ -399,12 +1210,12 @@ impl MyApp {
                 });
             });
             if ui.input(|r| r.pointer.primary_clicked()) {
-                self.to_main_window(ctx);
+                self.goto_main_window(ctx);
             }
         });
     }
 
-    fn to_main_window(&mut self, ctx: &egui::Context) {
+    fn goto_main_window(&mut self, ctx: &egui::Context) {
         println!("--- Convert to main window! ---");
         self.app = AppWindow::Main;
         ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(self.window_pos));
@@ -414,9 +1225,23 @@ impl MyApp {
         ));
     }
 
-    fn add_achievement(&mut self, ctx: &egui::Context, achievement: achievement::AppAchievement) {
-        println!("Add achievement: {}", achievement.title);
-        self.achievements.push(achievement);
+    fn add_achievement(&mut self, ctx: &egui::Context, event: AchievementEvent) {
+        println!(
+            "Add achievement: {} ({:?}, {}/{})",
+            event.achievement.title, event.kind, event.earned, event.total
+        );
+        self.notifiers.notify(&event, self.headline(&event));
+        let entry = self.history.record(&event);
+        self.history_entries.push(entry);
+        if event.kind == EventKind::Completion {
+            self.save_perfect_game(&event);
+        }
+        if !self.notifiers.get_popup() {
+            // no popup to wait for, announce it now
+            self.announce(&event);
+            return;
+        }
+        self.achievements.push(event);
         if self.app == AppWindow::Achievement && !self.visiblilty {
             self.show(ctx);
         } else if self.app == AppWindow::Main {
@@ -424,18 +1249,97 @@ impl MyApp {
         }
     }
 
+    /// Text card of a 100% completion: game, completion date and the time from the first
+    /// unlock to the last one
+    fn perfect_game_summary(&self, stats: &statistics::Statistics) -> String {
+        let time = |t: Option<u64>| t.map(statistics::format_time).unwrap_or("-".to_string());
+        let span = stats
+            .first
+            .zip(stats.latest)
+            .map(|(first, latest)| statistics::format_duration(latest - first))
+            .unwrap_or("-".to_string());
+        self.i18n
+            .tr("perfect_summary")
+            .replace("{game}", &self.setting.get_game_name())
+            .replace("{total}", &stats.total.to_string())
+            .replace("{date}", &time(stats.latest))
+            .replace("{first}", &time(stats.first))
+            .replace("{span}", &span)
+    }
+
+    fn save_perfect_game(&mut self, event: &AchievementEvent) {
+        // the table is only updated after the event, so put the last unlock in place first
+        let mut achievements = self.app_achievenemt.clone();
+        if let Some(ac) = achievements
+            .iter_mut()
+            .find(|ac| ac.id == event.achievement.id)
+        {
+            *ac = event.achievement.clone();
+        }
+        let summary = self.perfect_game_summary(&statistics::Statistics::new(&achievements));
+        println!("{}", summary);
+        if let Some(path) = self.history.save_perfect_game(&summary) {
+            println!("Perfect game summary is saved to {:?}", path);
+            let fonts = self.setting.get_fonts();
+            let achievement = event.achievement.clone();
+            let headline = self.i18n.tr("perfect_game").to_string();
+            let path = path.with_extension("png");
+            std::thread::spawn(move || {
+                let card = showcase::Card {
+                    achievement: &achievement,
+                    headline: &headline,
+                    note: &summary,
+                    border: Some(showcase::GOLD),
+                };
+                let text = showcase::TextRenderer::new(&fonts);
+                match showcase::save(&showcase::render_card(&text, &card), &path) {
+                    Ok(()) => println!("Perfect game card is saved to {:?}", path),
+                    Err(e) => println!("Can not save {:?}: {}", path, e),
+                }
+            });
+        }
+    }
+
+    /// render the grid of earned achievements into the data dir, on a worker thread
+    fn save_showcase(&mut self) {
+        self.showcase_status = Some(self.i18n.tr("rendering").to_string());
+        self.rendering_showcase = true;
+        let fonts = self.setting.get_fonts();
+        let game = self.setting.get_game_name();
+        let achievements = self.app_achievenemt.clone();
+        let path = std::path::PathBuf::from(self.setting.get_data_dir()).join("showcase.png");
+        let saved_to = self.i18n.tr("saved_to").to_string();
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            // the fonts are only loaded for this image
+            let text = showcase::TextRenderer::new(&fonts);
+            let image = showcase::render_grid(&text, &game, &achievements);
+            let status = match showcase::save(&image, &path) {
+                Ok(()) => format!("{} {}", saved_to, path.display()),
+                Err(e) => format!("⚠ {}", e),
+            };
+            let _ = sender.send(AppCmd::ShowcaseSaved(status));
+        });
+    }
+
+    /// a hidden window is not in the taskbar either, where it can not be hidden it is shrunk
     fn hide(&mut self, ctx: &egui::Context) {
         println!("Hide view");
-        // ctx.send_viewport_cmd(egui::ViewportCommand::Transparent(true));
-        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(false));
-        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize([0.0, 0.0].into()));
-        ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(true));
-
+        if self.can_hide {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        } else {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize([0.0, 0.0].into()));
+            ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(true));
+        }
         self.visiblilty = false;
     }
 
     fn show(&mut self, ctx: &egui::Context) {
         println!("Show view");
+        if self.can_hide {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        }
         ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
         ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(
             self.setting.get_achievement_window_size().into(),
@@ -444,11 +1348,109 @@ impl MyApp {
         self.visiblilty = true;
     }
 
+    /// what the tray menu depends on besides the games, it is rebuilt when this changes
+    fn tray_state(
+        setting: &Setting,
+        i18n: &i18n::I18n,
+        notifiers: &notifier::Notifiers,
+    ) -> (bool, bool, String) {
+        (
+            notifiers.get_popup(),
+            setting.get_mute(),
+            i18n.get_language().to_string(),
+        )
+    }
+
+    /// texts and state of the tray menu
+    fn tray_menu(
+        setting: &Setting,
+        i18n: &i18n::I18n,
+        notifiers: &notifier::Notifiers,
+        games: Vec<games::Game>,
+    ) -> tray::Menu {
+        tray::Menu {
+            title: setting.get_game_name(),
+            open: i18n.tr("tray_open").to_string(),
+            popup: i18n.tr("tray_popup").to_string(),
+            mute: i18n.tr("mute").to_string(),
+            switch_game: i18n.tr("tray_switch_game").to_string(),
+            quit: i18n.tr("tray_quit").to_string(),
+            popup_on: notifiers.get_popup(),
+            muted: setting.get_mute(),
+            appid: setting.get_app_id(),
+            games,
+        }
+    }
+
+    fn tray_action(&mut self, ctx: &egui::Context, action: tray::Action) {
+        println!("Tray: {:?}", action);
+        match action {
+            tray::Action::Open => {
+                if self.app == AppWindow::Achievement {
+                    if !self.visiblilty {
+                        self.show(ctx);
+                    }
+                    self.goto_main_window(ctx);
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            tray::Action::TogglePopup => {
+                let popup = !self.notifiers.get_popup();
+                self.notifiers.set_popup(popup);
+            }
+            tray::Action::ToggleMute => {
+                let mute = !self.setting.get_mute();
+                self.setting.set_mute(mute);
+                self.sfx.set_mute(mute);
+            }
+            tray::Action::SwitchGame(appid) => {
+                if appid == self.setting.get_app_id() {
+                    return;
+                }
+                let Some(game) = self.games.iter().find(|g| g.appid == appid) else {
+                    return;
+                };
+                match game.launch(&self.setting) {
+                    Ok(()) => self.close(ctx),
+                    Err(e) => println!("Can not switch to {}: {}", game.name, e),
+                }
+            }
+            tray::Action::Quit => self.close(ctx),
+        }
+    }
+
     fn close(&mut self, ctx: &egui::Context) {
+        self.quitting = true;
         drop(self.watcher.take());
         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
     }
 
+    /// indices of `app_achievenemt` passing the filter, in the order of the table rows
+    fn row_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.app_achievenemt.len())
+            .filter(|i| {
+                let ac = &self.app_achievenemt[*i];
+                self.filter.matches(ac, self.notes.get(&ac.id))
+            })
+            .collect();
+        if let Some(rarest_first) = self.rarity_sort {
+            order.sort_by(|a, b| {
+                let (a, b) = (
+                    self.app_achievenemt[*a].rarity,
+                    self.app_achievenemt[*b].rarity,
+                );
+                match (a, b) {
+                    (Some(a), Some(b)) if rarest_first => a.total_cmp(&b),
+                    (Some(a), Some(b)) => b.total_cmp(&a),
+                    // achievements without rarity stay at the end
+                    (a, b) => b.is_some().cmp(&a.is_some()),
+                }
+            });
+        }
+        order
+    }
+
     fn draw_table(&mut self, ui: &mut egui::Ui) {
         let available_height = ui.available_height();
         let mut table = egui_extras::TableBuilder::new(ui)
@@ -460,56 +1462,89 @@ impl MyApp {
             .column(egui_extras::Column::auto())
             .column(egui_extras::Column::auto().clip(true).at_least(60.0))
             .column(egui_extras::Column::auto())
+            .column(egui_extras::Column::auto())
+            .column(egui_extras::Column::auto().clip(true).at_least(60.0))
             .column(egui_extras::Column::auto().clip(true).at_least(60.0))
             .column(egui_extras::Column::remainder().clip(true).at_least(60.0))
             .min_scrolled_height(0.0)
             .max_scroll_height(available_height)
             .sense(egui::Sense::click());
-        if let Some(row_index) = self.scroll_to.take() {
-            table = table.scroll_to_row(row_index, None);
+        let order = self.row_order();
+        if let Some(index) = self.scroll_to.take() {
+            if let Some(row_index) = order.iter().position(|i| *i == index) {
+                table = table.scroll_to_row(row_index, None);
+            }
         }
+        let mut toggle_planned = None;
+        let mut edit_note = None;
+        let mut clicked = None;
+        let mut rarity_sort = self.rarity_sort;
+        let secondary = self.setting.get_secondary_language();
+        let row_height = if secondary.is_some() { 60.0 } else { 45.0 };
         table
             .header(20.0, |mut header| {
                 header.col(|ui| {
-                    ui.strong("Id");
+                    ui.strong(self.i18n.tr("col_id"));
+                });
+                header.col(|ui| {
+                    ui.strong(self.i18n.tr("col_icon"));
+                });
+                header.col(|ui| {
+                    ui.strong(self.i18n.tr("col_state"));
                 });
                 header.col(|ui| {
-                    ui.strong("Icon");
+                    ui.strong(self.i18n.tr("col_date"));
                 });
                 header.col(|ui| {
-                    ui.strong("State");
+                    let arrow = match rarity_sort {
+                        Some(true) => " ⏶",
+                        Some(false) => " ⏷",
+                        None => "",
+                    };
+                    let text =
+                        egui::RichText::new(format!("{}{}", self.i18n.tr("col_rarity"), arrow));
+                    if ui.button(text.strong()).clicked() {
+                        // rarest first, then most common first, then the schema order
+                        rarity_sort = match rarity_sort {
+                            None => Some(true),
+                            Some(true) => Some(false),
+                            Some(false) => None,
+                        };
+                    }
                 });
                 header.col(|ui| {
-                    ui.strong("Date");
+                    ui.strong(self.i18n.tr("col_visibility"));
                 });
                 header.col(|ui| {
-                    ui.strong("Visibility");
+                    ui.strong(self.i18n.tr("col_notes"));
                 });
                 header.col(|ui| {
-                    ui.strong("Title");
+                    ui.strong(self.i18n.tr("col_title"));
                 });
                 header.col(|ui| {
-                    ui.strong("Description");
+                    ui.strong(self.i18n.tr("col_description"));
                 });
             })
             .body(|mut body| {
-                for ac in &self.app_achievenemt {
-                    body.row(45.0, |mut row| {
-                        // row.set_selected(self.selection.contains(&row_index));
+                for ac in order.iter().map(|i| &self.app_achievenemt[*i]) {
+                    body.row(row_height, |mut row| {
+                        row.set_selected(self.selected.as_ref() == Some(&ac.id));
                         row.col(|ui| {
                             ui.label(egui::RichText::new(&ac.id).size(16.0));
                         });
                         row.col(|ui| {
-                            ui.add(
-                                egui::Image::new(&format!("file://{}", ac.icon))
-                                    .fit_to_exact_size([40.0, 40.0].into())
-                                    .rounding(5.0),
+                            self.achievement_icon(
+                                ui,
+                                ac,
+                                ac.state,
+                                icon_cache::TABLE_SIZE as f32,
+                                5.0,
                             );
                         });
                         row.col(|ui| {
                             if ac.state {
                                 ui.label(
-                                    egui::RichText::new("Achieved!")
+                                    egui::RichText::new(self.i18n.tr("achieved"))
                                         .size(16.0)
                                         .color(egui::Color32::DARK_GREEN),
                                 );
@@ -524,6 +1559,18 @@ impl MyApp {
                                 ui.label(&ac.date);
                             }
                         });
+                        row.col(|ui| match ac.rarity {
+                            Some(percent) => {
+                                let text = egui::RichText::new(format!("{:.1}%", percent));
+                                match tier_color(rarity::Tier::of(percent)) {
+                                    Some(color) => ui.label(text.strong().color(color)),
+                                    None => ui.label(text),
+                                };
+                            }
+                            None => {
+                                ui.add(egui::Separator::default().horizontal());
+                            }
+                        });
                         row.col(|ui| {
                             ui.centered_and_justified(|ui| {
                                 if ac.visibility {
@@ -542,43 +1589,197 @@ impl MyApp {
                             });
                         });
                         row.col(|ui| {
-                            ui.label(egui::RichText::new(&ac.title).heading().size(18.0));
+                            let note = self.notes.get(&ac.id);
+                            let mut planned = note.is_some_and(|n| n.planned);
+                            if ui
+                                .checkbox(&mut planned, "")
+                                .on_hover_text(self.i18n.tr("planned"))
+                                .changed()
+                            {
+                                toggle_planned = Some(ac.id.clone());
+                            }
+                            let edit = ui.button("✏");
+                            let edit = match note.filter(|n| !n.text.is_empty()) {
+                                Some(note) => edit.on_hover_text(&note.text),
+                                None => edit,
+                            };
+                            if edit.clicked() {
+                                edit_note = Some(ac.id.clone());
+                            }
+                            for tag in note.iter().flat_map(|n| &n.tags) {
+                                ui.label(egui::RichText::new(tag).small().weak());
+                            }
                         });
                         row.col(|ui| {
-                            // NOTE: `Label` overrides some of the wrapping settings, e.g. wrap width
-                            if ac.visibility {
-                                ui.label(
-                                    egui::RichText::new(&ac.description)
-                                        .size(16.0)
-                                        .color(egui::Color32::DARK_GRAY),
-                                );
-                            } else {
-                                ui.label(
-                                    egui::RichText::new(&ac.description)
-                                        .size(16.0)
-                                        .color(egui::Color32::GRAY),
-                                );
-                            }
+                            ui.vertical(|ui| {
+                                let title =
+                                    ui.label(egui::RichText::new(&ac.title).heading().size(18.0));
+                                if !ac.missing_languages.is_empty() {
+                                    let names: Vec<&str> = ac
+                                        .missing_languages
+                                        .iter()
+                                        .map(|l| language::native_name(l).unwrap_or(l))
+                                        .collect();
+                                    title.on_hover_text(format!(
+                                        "{} {}",
+                                        self.i18n.tr("missing_languages"),
+                                        names.join(", ")
+                                    ));
+                                }
+                                if let Some(title) =
+                                    secondary.as_deref().and_then(|l| ac.get_title_in(l))
+                                {
+                                    ui.label(egui::RichText::new(title).size(14.0).weak());
+                                }
+                            });
+                        });
+                        row.col(|ui| {
+                            ui.vertical(|ui| {
+                                // NOTE: `Label` overrides some of the wrapping settings, e.g. wrap width
+                                if ac.visibility {
+                                    ui.label(
+                                        egui::RichText::new(&ac.description)
+                                            .size(16.0)
+                                            .color(egui::Color32::DARK_GRAY),
+                                    );
+                                } else {
+                                    ui.label(
+                                        egui::RichText::new(&ac.description)
+                                            .size(16.0)
+                                            .color(egui::Color32::GRAY),
+                                    );
+                                }
+                                if let Some(description) =
+                                    secondary.as_deref().and_then(|l| ac.get_description_in(l))
+                                {
+                                    ui.label(egui::RichText::new(description).size(13.0).weak());
+                                }
+                            });
                         });
+                        if row.response().clicked() {
+                            clicked = Some(ac.id.clone());
+                        }
                     });
                 }
             });
+        self.rarity_sort = rarity_sort;
+        if let Some(id) = clicked {
+            self.select(id);
+        }
+        if let Some(id) = toggle_planned {
+            let mut note = self.notes.get(&id).cloned().unwrap_or_default();
+            note.planned = !note.planned;
+            self.notes.set(&id, note);
+        }
+        if let Some(id) = edit_note {
+            let note = self.notes.get(&id).cloned().unwrap_or_default();
+            self.note_editor = Some((id, note, String::new()));
+        }
+    }
+
+    /// the icons of the listed achievements in a grid, click one to open its panel
+    fn draw_gallery(&mut self, ui: &mut egui::Ui) {
+        let size = self.setting.get_gallery_tile_size();
+        let mut clicked = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for i in self.row_order() {
+                        let ac = &self.app_achievenemt[i];
+                        let response = self
+                            .achievement_icon(ui, ac, ac.state, size, size / 10.0)
+                            .interact(egui::Sense::click());
+                        if self.selected.as_ref() == Some(&ac.id) {
+                            ui.painter().rect_stroke(
+                                response.rect.expand(2.0),
+                                size / 10.0,
+                                ui.visuals().selection.stroke,
+                            );
+                        }
+                        let response = response.on_hover_ui(|ui| {
+                            ui.strong(&ac.title);
+                            ui.label(&ac.description);
+                            if ac.date.is_empty() {
+                                ui.label(egui::RichText::new(self.i18n.tr("not_earned")).weak());
+                            } else {
+                                ui.label(egui::RichText::new(&ac.date).weak());
+                            }
+                        });
+                        if response.clicked() {
+                            clicked = Some(ac.id.clone());
+                        }
+                    }
+                });
+            });
+        if let Some(id) = clicked {
+            self.select(id);
+        }
+    }
+
+    /// open the panel of the achievement, a second click closes it
+    fn select(&mut self, id: String) {
+        self.selected = if self.selected.as_ref() == Some(&id) {
+            None
+        } else {
+            Some(id)
+        };
+    }
+
+    /// the cached earned or locked icon, empty space while it is decoded,
+    /// or a placeholder if it can not be loaded
+    fn achievement_icon(
+        &self,
+        ui: &mut egui::Ui,
+        ac: &achievement::AppAchievement,
+        earned: bool,
+        size: f32,
+        rounding: f32,
+    ) -> egui::Response {
+        match self.icons.achievement(ui.ctx(), ac, earned, size as u32) {
+            icon_cache::Icon::Loaded(icon) => ui.add(
+                egui::Image::new(&icon)
+                    .fit_to_exact_size([size, size].into())
+                    .rounding(rounding),
+            ),
+            icon_cache::Icon::Loading => {
+                ui.allocate_exact_size([size, size].into(), egui::Sense::hover())
+                    .1
+            }
+            icon_cache::Icon::Missing => placeholder_icon(ui, ac, earned, size, rounding),
+        }
+    }
+
+    /// the localized state line of an achievement
+    fn headline(&self, event: &AchievementEvent) -> &str {
+        if event.kind == EventKind::Completion {
+            self.i18n.tr("perfect_game")
+        } else if event.achievement.state {
+            self.i18n.tr("popup_gained")
+        } else {
+            self.i18n.tr("popup_lost")
+        }
+    }
+
+    /// play the sound and speak the event
+    fn announce(&self, event: &AchievementEvent) {
+        self.sfx.play(event.kind);
+        self.speaker
+            .announce(&event.achievement, self.headline(event));
     }
 
     // get the pop up at self.achievement
     // return true if it contains any
     fn get_pop_up_window(&mut self) -> bool {
         if self.time_left <= 0.001 {
-            self.achievement = self.achievements.pop();
+            let popped = self.achievements.pop();
             self.time_left = self.setting.get_pop_up_time();
-            if !self.achievement.is_none() {
-                if self.achievement.as_ref().unwrap().state {
-                    self.sfx.play_get();
-                } else {
-                    self.sfx.play_lose();
-                }
+            if let Some(event) = &popped {
+                self.announce(event);
                 self.start_time = std::time::Instant::now();
+                self.achievement_kind = event.kind;
             }
+            self.achievement = popped.map(|event| event.achievement);
         }
         if self.achievement.is_none() {
             self.time_left = 0.0;
@@ -592,41 +1793,33 @@ impl MyApp {
     }
 }
 
-struct SoundEffects {
-    _stream: rodio::OutputStream,
-    _handle: rodio::OutputStreamHandle,
-    sink: rodio::Sink,
+/// the initials of the title on a colored tile, for an achievement without a usable icon
+fn placeholder_icon(
+    ui: &mut egui::Ui,
+    ac: &achievement::AppAchievement,
+    earned: bool,
+    size: f32,
+    rounding: f32,
+) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size([size, size].into(), egui::Sense::hover());
+    let [r, g, b] = icon_cache::placeholder_color(&ac.id, earned);
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, rounding, egui::Color32::from_rgb(r, g, b));
+    painter.text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        icon_cache::initials(&ac.title),
+        egui::FontId::proportional(size * 0.4),
+        egui::Color32::from_gray(230),
+    );
+    response
 }
-impl SoundEffects {
-    const BYTES: [&[u8]; 6] = [
-        include_bytes!("../assets/sound1.mp3"),
-        include_bytes!("../assets/sound2.mp3"),
-        include_bytes!("../assets/sound3.mp3"),
-        include_bytes!("../assets/sound4.mp3"),
-        include_bytes!("../assets/sound5.mp3"),
-        include_bytes!("../assets/sound6.mp3"),
-    ];
-    fn new() -> Self {
-        let (_stream, _handle) = rodio::OutputStream::try_default().unwrap();
-        let sink = rodio::Sink::try_new(&_handle).unwrap();
-        Self {
-            _stream,
-            _handle,
-            sink,
-        }
-    }
-    fn play_get(&self) {
-        let source1 = rodio::Decoder::new(std::io::Cursor::new(Self::BYTES[2])).unwrap();
-        let source2 = rodio::Decoder::new(std::io::Cursor::new(Self::BYTES[1])).unwrap();
-
-        self.sink.append(source1);
-        self.sink.append(source2);
-    }
-    fn play_lose(&self) {
-        let source1 = rodio::Decoder::new(std::io::Cursor::new(Self::BYTES[0])).unwrap();
-        let source2 = rodio::Decoder::new(std::io::Cursor::new(Self::BYTES[3])).unwrap();
 
-        self.sink.append(source1);
-        self.sink.append(source2);
+/// border and text color of a rarity tier, None for common achievements
+fn tier_color(tier: rarity::Tier) -> Option<egui::Color32> {
+    match tier {
+        rarity::Tier::Rare => Some(egui::Color32::GOLD),
+        rarity::Tier::Uncommon => Some(egui::Color32::from_rgb(160, 170, 185)),
+        rarity::Tier::Common => None,
     }
 }
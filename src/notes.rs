@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{achievement::AppAchievement, setting::Setting};
+
+const NOTES_NAME: &str = "notes.json";
+
+/// tags offered for every achievement, others can be typed in
+pub const SUGGESTED_TAGS: [&str; 3] = ["missable", "grind", "multiplayer"];
+
+/// What the player keeps about an achievement
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Note {
+    pub text: String,
+    pub tags: Vec<String>,
+    /// on the personal checklist of the run
+    pub planned: bool,
+}
+
+impl Note {
+    pub fn is_empty(&self) -> bool {
+        *self == Note::default()
+    }
+}
+
+/// Notes of the game by achievement name, kept in `{data dir}/notes.json`.
+///
+/// The data dir is per appid, and the Goldberg save and schema are never written.
+pub struct Notes {
+    path: PathBuf,
+    notes: BTreeMap<String, Note>,
+    /// the file is there but can not be read, so it is not written over
+    read_only: bool,
+}
+
+impl Notes {
+    pub fn new(setting: &Setting) -> Self {
+        Self::load(PathBuf::from(setting.get_data_dir()).join(NOTES_NAME))
+    }
+
+    /// a file that can not be parsed is moved to `notes.json.bad`, not replaced by the next note
+    fn load(path: PathBuf) -> Self {
+        let mut read_only = false;
+        let notes = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                let aside = path.with_extension("json.bad");
+                println!(
+                    "Can not parse notes {:?}: {}, it is moved to {:?}",
+                    path, e, aside
+                );
+                if let Err(e) = fs::rename(&path, &aside) {
+                    println!("Can not move {:?}: {}, notes are not saved", path, e);
+                    read_only = true;
+                }
+                BTreeMap::new()
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                println!("Can not read notes {:?}: {}, notes are not saved", path, e);
+                read_only = true;
+                BTreeMap::new()
+            }
+        };
+        Self {
+            path,
+            notes,
+            read_only,
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Note> {
+        self.notes.get(id)
+    }
+
+    /// replace the note of the achievement and save the file, an empty note is removed
+    pub fn set(&mut self, id: &str, note: Note) {
+        if note.is_empty() {
+            self.notes.remove(id);
+        } else {
+            self.notes.insert(id.to_string(), note);
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        if self.read_only {
+            return;
+        }
+        let written = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                fs::write(
+                    &self.path,
+                    serde_json::to_string_pretty(&self.notes).unwrap(),
+                )
+            });
+        if let Err(e) = written {
+            println!("Can not save notes {:?}: {}", self.path, e);
+        }
+    }
+
+    /// the suggested tags, then the others in use, sorted
+    pub fn get_tags(&self) -> Vec<String> {
+        let mut others: Vec<&String> = self
+            .notes
+            .values()
+            .flat_map(|note| &note.tags)
+            .filter(|tag| !SUGGESTED_TAGS.contains(&tag.as_str()))
+            .collect();
+        others.sort();
+        others.dedup();
+        SUGGESTED_TAGS
+            .iter()
+            .map(|tag| tag.to_string())
+            .chain(others.into_iter().cloned())
+            .collect()
+    }
+}
+
+/// Which achievements are listed, shared by the views of the main window
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    /// part of the id, title, description or note, in any case
+    pub text: String,
+    pub tag: Option<String>,
+    pub planned_only: bool,
+}
+
+impl Filter {
+    pub fn matches(&self, ac: &AppAchievement, note: Option<&Note>) -> bool {
+        if self.planned_only && !note.is_some_and(|n| n.planned) {
+            return false;
+        }
+        if let Some(tag) = &self.tag {
+            if !note.is_some_and(|n| n.tags.contains(tag)) {
+                return false;
+            }
+        }
+        let text = self.text.trim().to_lowercase();
+        text.is_empty()
+            || [&ac.id, &ac.title, &ac.description]
+                .into_iter()
+                .chain(note.map(|n| &n.text))
+                .any(|s| s.to_lowercase().contains(&text))
+    }
+}
+
+/// write the achievements with their notes, as CSV, or as JSON for a `.json` path
+pub fn export(achievements: &[AppAchievement], notes: &Notes, path: &Path) -> Result<(), String> {
+    let empty = Note::default();
+    let text = if path.extension().is_some_and(|e| e == "json") {
+        let rows: Vec<serde_json::Value> = achievements
+            .iter()
+            .map(|ac| {
+                let note = notes.get(&ac.id).unwrap_or(&empty);
+                serde_json::json!({
+                    "id": ac.id,
+                    "title": ac.title,
+                    "description": ac.description,
+                    "earned": ac.state,
+                    "date": ac.date,
+                    "rarity": ac.rarity,
+                    "hidden": !ac.visibility,
+                    "planned": note.planned,
+                    "tags": note.tags,
+                    "note": note.text,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap()
+    } else {
+        let mut csv =
+            String::from("id,title,description,earned,date,rarity,hidden,planned,tags,note\n");
+        for ac in achievements {
+            let note = notes.get(&ac.id).unwrap_or(&empty);
+            let fields = [
+                ac.id.clone(),
+                ac.title.clone(),
+                ac.description.clone(),
+                ac.state.to_string(),
+                ac.date.clone(),
+                ac.rarity.map(|r| r.to_string()).unwrap_or_default(),
+                (!ac.visibility).to_string(),
+                note.planned.to_string(),
+                note.tags.join(";"),
+                note.text.clone(),
+            ];
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|f| format!("\"{}\"", f.replace('"', "\"\"")))
+                .collect();
+            csv += &fields.join(",");
+            csv.push('\n');
+        }
+        csv
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, text).map_err(|e| e.to_string())
+}
+
+#[test]
+fn notes_filter_and_export() {
+    let dir = crate::temp_dir::TempDir::new("notes");
+    let mut notes = Notes::load(dir.join(NOTES_NAME));
+    let note = Note {
+        text: "Before chapter 3, \"the bridge\"".to_string(),
+        tags: vec!["missable".to_string(), "speedrun".to_string()],
+        planned: true,
+    };
+    notes.set("ACH_BRIDGE", note.clone());
+    notes.set("ACH_OTHER", Note::default());
+    let notes = Notes::load(dir.join(NOTES_NAME));
+    assert_eq!(notes.get("ACH_BRIDGE"), Some(&note));
+    assert_eq!(notes.get("ACH_OTHER"), None);
+    assert_eq!(notes.get_tags().last().unwrap(), "speedrun");
+
+    let mut ac = AppAchievement {
+        id: "ACH_BRIDGE".to_string(),
+        ..Default::default()
+    };
+    let mut filter = Filter {
+        text: "BRIDGE".to_string(),
+        tag: Some("missable".to_string()),
+        planned_only: true,
+    };
+    assert!(filter.matches(&ac, notes.get(&ac.id)));
+    assert!(!filter.matches(&ac, None));
+    filter.text = "chapter".to_string();
+    ac.id = "ACH_1".to_string();
+    assert!(filter.matches(&ac, Some(&note)));
+
+    ac.id = "ACH_BRIDGE".to_string();
+    export(&[ac], &notes, &dir.join("export.csv")).unwrap();
+    let csv = fs::read_to_string(dir.join("export.csv")).unwrap();
+    assert!(
+        csv.ends_with("\"true\",\"missable;speedrun\",\"Before chapter 3, \"\"the bridge\"\"\"\n")
+    );
+}
+
+#[test]
+fn broken_notes_are_kept() {
+    let dir = crate::temp_dir::TempDir::new("bad_notes");
+    let path = dir.join(NOTES_NAME);
+    fs::write(&path, "{\"ACH_WIN\": {\"text\": ").unwrap();
+    let mut notes = Notes::load(path.clone());
+    assert_eq!(notes.get("ACH_WIN"), None);
+    let note = Note {
+        text: "Later".to_string(),
+        ..Default::default()
+    };
+    notes.set("ACH_PLAY", note.clone());
+    let bad = fs::read_to_string(dir.join("notes.json.bad")).unwrap();
+    let notes = Notes::load(path);
+    assert_eq!(bad, "{\"ACH_WIN\": {\"text\": ");
+    assert_eq!(notes.get("ACH_PLAY"), Some(&note));
+}
@@ -0,0 +1,237 @@
+use std::{sync::mpsc, thread};
+
+use crate::{
+    achievement::AchievementEvent, server::Server, setting::Setting, webhook::WebhookNotifier,
+};
+
+/// Name of the egui popup window in the `notifiers` setting
+pub const POPUP: &str = "popup";
+
+/// A way to tell the user about an achievement event, besides the egui popup.
+/// `notify` runs on a worker thread and may block.
+pub trait Notifier: Send {
+    fn name(&self) -> &'static str;
+    /// headline is the localized state line, like 'Achievement Gained! CONGRATS!'
+    fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String>;
+}
+
+/// freedesktop notifications, sent to org.freedesktop.Notifications over the session D-Bus
+#[cfg(all(unix, not(target_os = "macos")))]
+pub struct FreeDesktopNotifier {
+    timeout: u32,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl FreeDesktopNotifier {
+    /// the notification shown for the event
+    fn notification(&self, event: &AchievementEvent, headline: &str) -> notify_rust::Notification {
+        let ac = &event.achievement;
+        notify_rust::Notification::new()
+            .appname("Steam Achievements Reminder")
+            .summary(&format!("{} {}", headline, ac.title))
+            .body(&ac.description)
+            .icon(ac.get_state_icon())
+            .image_path(ac.get_state_icon())
+            .timeout(notify_rust::Timeout::Milliseconds(self.timeout))
+            .finalize()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Notifier for FreeDesktopNotifier {
+    fn name(&self) -> &'static str {
+        "freedesktop"
+    }
+
+    fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String> {
+        self.notification(event, headline)
+            .show()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Windows toast notifications
+#[cfg(windows)]
+pub struct ToastNotifier;
+
+#[cfg(windows)]
+impl Notifier for ToastNotifier {
+    fn name(&self) -> &'static str {
+        "toast"
+    }
+
+    fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String> {
+        let ac = &event.achievement;
+        notify_rust::Notification::new()
+            .summary(&format!("{} {}", headline, ac.title))
+            .body(&ac.description)
+            .image_path(ac.get_state_icon())
+            .show()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// All notifiers selected by the `notifiers` setting, like ["popup", "freedesktop"].
+///
+/// The egui popup is only a flag here, it is drawn by the app itself.
+/// The others receive every event on a worker thread, in the order of the setting.
+/// Webhooks are added whenever the `webhooks` setting is not empty,
+/// the local server whenever `server_address` is set.
+pub struct Notifiers {
+    sender: Option<mpsc::Sender<(AchievementEvent, String)>>,
+    popup: bool,
+    webhook: Option<WebhookNotifier>,
+    server: Option<Server>,
+}
+
+impl Notifiers {
+    pub fn new(setting: &Setting) -> Self {
+        let mut popup = false;
+        let mut notifiers: Vec<Box<dyn Notifier>> = vec![];
+        for name in setting.get_notifiers() {
+            match name.as_str() {
+                POPUP => popup = true,
+                #[cfg(all(unix, not(target_os = "macos")))]
+                "freedesktop" => notifiers.push(Box::new(FreeDesktopNotifier {
+                    timeout: (setting.get_pop_up_time() * 1000.0) as u32,
+                })),
+                #[cfg(windows)]
+                "toast" => notifiers.push(Box::new(ToastNotifier)),
+                _ => println!("Notifier {:?} is not available on this platform", name),
+            }
+        }
+        let webhook = WebhookNotifier::new(setting);
+        if let Some(webhook) = &webhook {
+            notifiers.push(Box::new(webhook.clone()));
+        }
+        let server = Server::new(setting);
+        if let Some(server) = &server {
+            notifiers.push(Box::new(server.clone()));
+        }
+        let mut slf = Self::with_notifiers(notifiers, popup);
+        slf.webhook = webhook;
+        slf.server = server;
+        slf
+    }
+
+    pub fn with_notifiers(notifiers: Vec<Box<dyn Notifier>>, popup: bool) -> Self {
+        let sender = if notifiers.is_empty() {
+            None
+        } else {
+            let (sender, receiver) = mpsc::channel::<(AchievementEvent, String)>();
+            thread::spawn(move || {
+                for (event, headline) in receiver {
+                    for notifier in &notifiers {
+                        if let Err(e) = notifier.notify(&event, &headline) {
+                            println!("Notifier {} failed: {}", notifier.name(), e);
+                        }
+                    }
+                }
+            });
+            Some(sender)
+        };
+        Self {
+            sender,
+            popup,
+            webhook: None,
+            server: None,
+        }
+    }
+
+    /// whether the egui popup window is used
+    pub fn get_popup(&self) -> bool {
+        self.popup
+    }
+
+    pub fn set_popup(&mut self, popup: bool) {
+        self.popup = popup;
+    }
+
+    pub fn get_webhook(&self) -> Option<&WebhookNotifier> {
+        self.webhook.as_ref()
+    }
+
+    pub fn get_server(&self) -> Option<&Server> {
+        self.server.as_ref()
+    }
+
+    /// send the event to every notifier but the popup
+    pub fn notify(&self, event: &AchievementEvent, headline: &str) {
+        if let Some(sender) = &self.sender {
+            sender.send((event.clone(), headline.to_string())).unwrap();
+        }
+    }
+}
+
+#[test]
+fn notifiers_dispatch_in_order() {
+    /// sends back its name and the headline of every event
+    struct Recorder(&'static str, mpsc::Sender<(&'static str, String)>);
+
+    impl Notifier for Recorder {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String> {
+            let _ = self
+                .1
+                .send((self.0, format!("{} {}", headline, event.achievement.id)));
+            Err("shown in the log only".to_string())
+        }
+    }
+
+    let (sender, received) = mpsc::channel();
+    let notifiers = Notifiers::with_notifiers(
+        vec![
+            Box::new(Recorder("first", sender.clone())),
+            Box::new(Recorder("second", sender)),
+        ],
+        false,
+    );
+    assert!(!notifiers.get_popup());
+    for id in ["ACH_1", "ACH_2"] {
+        let mut event = AchievementEvent::default();
+        event.achievement.id = id.to_string();
+        notifiers.notify(&event, "Gained");
+    }
+    let timeout = std::time::Duration::from_secs(10);
+    let received: Vec<_> = (0..4)
+        .map(|_| received.recv_timeout(timeout).unwrap())
+        .collect();
+    assert_eq!(
+        received,
+        [
+            ("first", "Gained ACH_1".to_string()),
+            ("second", "Gained ACH_1".to_string()),
+            ("first", "Gained ACH_2".to_string()),
+            ("second", "Gained ACH_2".to_string()),
+        ]
+    );
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn freedesktop_notification() {
+    let notifier = FreeDesktopNotifier { timeout: 5000 };
+    let mut event = AchievementEvent::default();
+    event.achievement.title = "Winner".to_string();
+    event.achievement.description = "Win a game".to_string();
+    event.achievement.icon = "/img/win.jpg".to_string();
+    event.achievement.icon_gray = "/img/win_gray.jpg".to_string();
+    event.achievement.state = false;
+    let notification = notifier.notification(&event, "Lost");
+    assert_eq!(notification.appname, "Steam Achievements Reminder");
+    assert_eq!(notification.summary, "Lost Winner");
+    assert_eq!(notification.body, "Win a game");
+    assert_eq!(notification.icon, "/img/win_gray.jpg");
+    assert!(notification.hints.contains(&notify_rust::Hint::ImagePath(
+        "/img/win_gray.jpg".to_string()
+    )));
+    assert_eq!(
+        notification.timeout,
+        notify_rust::Timeout::Milliseconds(5000)
+    );
+}
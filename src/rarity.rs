@@ -0,0 +1,189 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::setting::Setting;
+
+/// global unlock percent under which an achievement is rare, with a gold border
+pub const RARE_PERCENT: f64 = 5.0;
+/// global unlock percent under which an achievement is uncommon, with a silver border
+pub const UNCOMMON_PERCENT: f64 = 20.0;
+
+const RARITY_NAME: &str = "rarity.json";
+
+/// How rare an achievement is, from its global unlock percent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tier {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Tier {
+    pub fn of(percent: f64) -> Self {
+        if percent < RARE_PERCENT {
+            Tier::Rare
+        } else if percent < UNCOMMON_PERCENT {
+            Tier::Uncommon
+        } else {
+            Tier::Common
+        }
+    }
+}
+
+fn rarity_path(setting: &Setting) -> PathBuf {
+    PathBuf::from(setting.get_data_dir()).join(RARITY_NAME)
+}
+
+/// Global unlock percents of the game, by achievement name.
+///
+/// They are kept in `{data dir}/rarity.json`; the file given by `--rarity` is imported there first.
+pub fn load(setting: &Setting) -> HashMap<String, f64> {
+    if let Some(file) = setting.get_rarity_import() {
+        match import(setting, &file) {
+            Ok(rarity) => return rarity,
+            Err(e) => println!("Can not import rarity from {:?}: {}", file, e),
+        }
+    }
+    fs::read_to_string(rarity_path(setting))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// parse the file and store it for the game
+pub fn import(setting: &Setting, file: &str) -> Result<HashMap<String, f64>, String> {
+    let text = fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let rarity = parse(&text)?;
+    let path = rarity_path(setting);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&rarity).unwrap()).map_err(|e| e.to_string())?;
+    println!(
+        "Import rarity of {} achievements to {:?}",
+        rarity.len(),
+        path
+    );
+    Ok(rarity)
+}
+
+/// Read unlock percents from any of:
+/// - the Steam Web API GetGlobalAchievementPercentagesForApp response,
+///   `{"achievementpercentages": {"achievements": [{"name": .., "percent": ..}]}}`
+/// - a JSON object `{"ACH_WIN": 12.5}`, or an array of `{"name": .., "percent": ..}`
+/// - CSV with a header naming an `apiname`/`name`/`id` and a `percent`/`rarity` column,
+///   or without header as `name,percent` lines
+pub fn parse(text: &str) -> Result<HashMap<String, f64>, String> {
+    let rarity = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(json) => parse_json(&json)?,
+        Err(_) => parse_csv(text)?,
+    };
+    if rarity.is_empty() {
+        Err("No unlock percent is found".to_string())
+    } else {
+        Ok(rarity)
+    }
+}
+
+fn parse_json(json: &serde_json::Value) -> Result<HashMap<String, f64>, String> {
+    let json = json
+        .pointer("/achievementpercentages/achievements")
+        .unwrap_or(json);
+    let mut rarity = HashMap::new();
+    if let Some(map) = json.as_object() {
+        for (name, value) in map {
+            if let Some(percent) = to_percent(value) {
+                rarity.insert(name.clone(), percent);
+            }
+        }
+    } else if let Some(list) = json.as_array() {
+        for entry in list {
+            let name = ["name", "apiname", "id"]
+                .iter()
+                .find_map(|key| entry.get(key)?.as_str());
+            let percent = ["percent", "percentage", "rarity"]
+                .iter()
+                .find_map(|key| to_percent(entry.get(key)?));
+            if let (Some(name), Some(percent)) = (name, percent) {
+                rarity.insert(name.to_string(), percent);
+            }
+        }
+    } else {
+        return Err("Expect an object or an array".to_string());
+    }
+    Ok(rarity)
+}
+
+/// a number, or a string like "12.5" or "12.5%"
+fn to_percent(value: &serde_json::Value) -> Option<f64> {
+    value.as_f64().or_else(|| parse_percent(value.as_str()?))
+}
+
+fn parse_percent(s: &str) -> Option<f64> {
+    s.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+/// fields of a CSV or TSV line, with quoted fields holding commas and doubled quotes
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let delimiter = if line.contains('\t') { '\t' } else { ',' };
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn parse_csv(text: &str) -> Result<HashMap<String, f64>, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let first = split_csv_line(lines.next().ok_or("The file is empty")?);
+    let mut rarity = HashMap::new();
+    let (name_column, percent_column) = if first.len() >= 2 && parse_percent(&first[1]).is_some() {
+        rarity.insert(first[0].clone(), parse_percent(&first[1]).unwrap());
+        (0, 1)
+    } else {
+        let header: Vec<String> = first.iter().map(|h| h.to_lowercase()).collect();
+        let name = ["apiname", "api name", "name", "id"]
+            .iter()
+            .find_map(|key| header.iter().position(|h| h == key))
+            .ok_or("No name column is found in the header")?;
+        let percent = header
+            .iter()
+            .position(|h| h.contains("percent") || h.contains("rarity") || h.contains('%'))
+            .ok_or("No percent column is found in the header")?;
+        (name, percent)
+    };
+    for fields in lines.map(split_csv_line) {
+        let name = fields.get(name_column);
+        let percent = fields.get(percent_column).and_then(|p| parse_percent(p));
+        if let (Some(name), Some(percent)) = (name, percent) {
+            rarity.insert(name.clone(), percent);
+        }
+    }
+    Ok(rarity)
+}
+
+#[test]
+fn parse_rarity_formats() {
+    let api = r#"{"achievementpercentages":{"achievements":[
+        {"name":"ACH_WIN","percent":"3.2"},{"name":"ACH_PLAY","percent":87.5}]}}"#;
+    let rarity = parse(api).unwrap();
+    assert_eq!(rarity["ACH_WIN"], 3.2);
+    assert_eq!(rarity["ACH_PLAY"], 87.5);
+    assert_eq!(parse(r#"{"ACH_WIN": "3.2%"}"#).unwrap()["ACH_WIN"], 3.2);
+    let csv = "Name,Description,API Name,Percentage\n\"Win\",\"Win a game, then \"\"gg\"\"\",ACH_WIN,3.2%\n";
+    assert_eq!(parse(csv).unwrap()["ACH_WIN"], 3.2);
+    assert_eq!(parse("ACH_WIN,3.2\nACH_PLAY,87.5").unwrap().len(), 2);
+    assert_eq!(Tier::of(3.2), Tier::Rare);
+    assert!(parse("[]").is_err());
+}
@@ -0,0 +1,234 @@
+use std::{collections::HashMap, fmt};
+
+use serde_json::Value;
+
+use crate::{achievement::AchievementRaw, rarity::split_csv_line};
+
+/// language of the texts of a single-language schema, unless told otherwise
+pub const DEFAULT_SCHEMA_LANGUAGE: &str = "english";
+
+/// Why a schema can not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    /// line and column of a JSON syntax error, from 1
+    pub position: Option<(usize, usize)>,
+}
+
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            position: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        let position = format!(" at line {} column {}", e.line(), e.column());
+        Self {
+            message: e.to_string().trim_end_matches(&position).to_string(),
+            position: Some((e.line(), e.column())),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some((line, column)) = self.position {
+            write!(f, " at line {} column {}", line, column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read achievements from any of:
+/// - the Goldberg `steam_settings/achievements.json` array
+/// - the Steam Web API GetSchemaForGame response,
+///   `{"game": {"availableGameStats": {"achievements": [..]}}}`
+/// - a SteamDB JSON export, an array of achievements with one text each
+/// - a SteamDB CSV export with a header, like `Name,Description,API Name,Hidden,Icon`
+///
+/// Single-language texts are stored under `language`.
+pub fn parse(text: &str, language: &str) -> Result<Vec<AchievementRaw>, ParseError> {
+    let achievements = match serde_json::from_str::<Value>(text) {
+        Ok(json) => parse_json(&json, language)?,
+        Err(e) if text.trim_start().starts_with(['[', '{']) => return Err(e.into()),
+        Err(_) => parse_csv(text, language)?,
+    };
+    if achievements.is_empty() {
+        Err("No achievement is found".to_string().into())
+    } else {
+        Ok(achievements)
+    }
+}
+
+fn parse_json(json: &Value, language: &str) -> Result<Vec<AchievementRaw>, String> {
+    let list = json
+        .pointer("/game/availableGameStats/achievements")
+        .or_else(|| json.get("achievements"))
+        .unwrap_or(json)
+        .as_array()
+        .ok_or("Expect an array of achievements")?;
+    // a bare array is the Goldberg file (or a SteamDB export), whose hidden is checked as written
+    let goldberg = json.is_array();
+    list.iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            from_json(entry, language, goldberg).ok_or(format!("Achievement {} has no name", i + 1))
+        })
+        .collect()
+}
+
+/// the first of the keys that is in the entry
+fn field<'a>(entry: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|key| entry.get(key))
+}
+
+/// texts by language, from an object of languages or from one text
+fn texts(value: Option<&Value>, language: &str) -> HashMap<String, String> {
+    match value {
+        Some(Value::Object(map)) => map
+            .iter()
+            .filter_map(|(l, text)| Some((l.clone(), text.as_str()?.to_string())))
+            .collect(),
+        Some(Value::String(text)) => [(language.to_string(), text.clone())].into(),
+        _ => HashMap::new(),
+    }
+}
+
+/// "0" or "1" from a string, a number or a bool
+fn to_hidden(value: Option<&Value>) -> String {
+    let hidden = match value {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::Number(n)) => n.as_f64().is_some_and(|n| n != 0.0),
+        Some(Value::String(s)) => is_true(s),
+        _ => false,
+    };
+    if hidden { "1" } else { "0" }.to_string()
+}
+
+fn is_true(s: &str) -> bool {
+    matches!(s.trim().to_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+/// with `literal_hidden` a string hidden is kept as it is, so validate can tell it is wrong
+fn from_json(entry: &Value, language: &str, literal_hidden: bool) -> Option<AchievementRaw> {
+    let string = |keys: &[&str]| {
+        field(entry, keys)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let name = string(&["name", "apiname", "api_name", "apiName"]);
+    if name.is_empty() {
+        return None;
+    }
+    let hidden = match field(entry, &["hidden"]) {
+        Some(Value::String(s)) if literal_hidden => s.clone(),
+        value => to_hidden(value),
+    };
+    Some(AchievementRaw {
+        hidden,
+        displayName: texts(
+            field(
+                entry,
+                &["displayName", "display_name", "displayname", "title"],
+            ),
+            language,
+        ),
+        description: texts(field(entry, &["description", "desc"]), language),
+        icon: string(&["icon"]),
+        icon_gray: string(&["icon_gray", "icongray", "iconGray"]),
+        name,
+    })
+}
+
+fn parse_csv(text: &str, language: &str) -> Result<Vec<AchievementRaw>, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or("The file is empty")?)
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let column = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| header.iter().position(|h| h == key))
+    };
+    // SteamDB names the title "Name" and the id "API Name"
+    let api_name = column(&["api name", "apiname", "api_name"]);
+    let name = api_name
+        .or(column(&["name", "id"]))
+        .ok_or("No name column is found in the header")?;
+    let title = column(&["display name", "displayname", "display_name", "title"])
+        .or(api_name.and(column(&["name"])));
+    let description = column(&["description", "desc"]);
+    let hidden = column(&["hidden"]);
+    let icon = column(&["icon"]);
+    let icon_gray = column(&["icon gray", "icongray", "icon_gray"]);
+    let mut achievements = vec![];
+    for fields in lines.map(split_csv_line) {
+        let get = |column: Option<usize>| {
+            column
+                .and_then(|c| fields.get(c))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let text = |column: Option<usize>| -> HashMap<String, String> {
+            let text = get(column);
+            if text.is_empty() {
+                HashMap::new()
+            } else {
+                [(language.to_string(), text)].into()
+            }
+        };
+        if get(Some(name)).is_empty() {
+            continue;
+        }
+        achievements.push(AchievementRaw {
+            hidden: if is_true(&get(hidden)) { "1" } else { "0" }.to_string(),
+            displayName: text(title),
+            description: text(description),
+            icon: get(icon),
+            icon_gray: get(icon_gray),
+            name: get(Some(name)),
+        });
+    }
+    Ok(achievements)
+}
+
+#[test]
+fn parse_schema_formats() {
+    let api = r#"{"game":{"gameName":"Spacewar","availableGameStats":{"achievements":[
+        {"name":"ACH_WIN_ONE_GAME","defaultvalue":0,"displayName":"Winner","hidden":0,
+         "description":"Win one game.",
+         "icon":"https://cdn.akamai.steamstatic.com/steamcommunity/public/images/apps/480/0a1b.jpg",
+         "icongray":"https://cdn.akamai.steamstatic.com/steamcommunity/public/images/apps/480/2c3d.jpg"},
+        {"name":"ACH_TRAVEL_FAR_ACCUM","displayName":"Interstellar","hidden":1,"description":""}]}}}"#;
+    let achievements = parse(api, "schinese").unwrap();
+    assert_eq!(achievements.len(), 2);
+    assert_eq!(achievements[0].displayName["schinese"], "Winner");
+    assert!(achievements[0].icon_gray.ends_with("2c3d.jpg"));
+    assert_eq!(achievements[1].hidden, "1");
+
+    let goldberg = r#"[{"hidden":"0","displayName":{"english":"Winner","german":"Sieger"},
+        "description":{"english":"Win"},"icon":"a.jpg","icon_gray":"b.jpg","name":"ACH_WIN"}]"#;
+    assert_eq!(parse(goldberg, "english").unwrap()[0].displayName.len(), 2);
+
+    let csv = "Name,Description,API Name,Hidden,Icon\n\
+        \"Winner\",\"Win, then win \"\"again\"\"\",ACH_WIN,true,0a1b\n";
+    let achievements = parse(csv, "english").unwrap();
+    assert_eq!(achievements[0].name, "ACH_WIN");
+    assert_eq!(achievements[0].displayName["english"], "Winner");
+    assert_eq!(
+        achievements[0].description["english"],
+        "Win, then win \"again\""
+    );
+    assert_eq!(achievements[0].hidden, "1");
+    assert_eq!(achievements[0].icon, "0a1b");
+    assert!(parse("[{\"displayName\":\"Winner\"}]", "english").is_err());
+    let error = parse("[\n  {\"name\": \"ACH_WIN\",}\n]", "english").unwrap_err();
+    assert_eq!(error.position, Some((2, 22)));
+    assert_eq!(error.to_string(), "trailing comma at line 2 column 22");
+}
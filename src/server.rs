@@ -0,0 +1,316 @@
+use std::{
+    fs,
+    io::{Cursor, Write},
+    path::Path,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::{
+    achievement::{AchievementEvent, AppAchievement},
+    notifier::Notifier,
+    setting::Setting,
+};
+
+/// browser source page for OBS, see the comment at its top for the query parameters
+const OVERLAY: &str = include_str!("../assets/overlay.html");
+
+/// how often an idle event stream gets a comment line, so closed clients are noticed
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct State {
+    game: String,
+    appid: u32,
+    achievements: Vec<AppAchievement>,
+    /// one per open `/api/events` stream
+    subscribers: Vec<mpsc::Sender<String>>,
+}
+
+/// Local HTTP server for overlays and dashboards, started when `server_address` is set.
+///
+/// - `GET /overlay`: the page to add as a browser source in OBS
+/// - `GET /api/achievements`: the achievements as shown in the table
+/// - `GET /api/completion`: `{game, appid, earned, total, completion}`, completion in percent
+/// - `GET /api/icons/{id}`: icon file of the achievement
+/// - `GET /api/events`: server-sent events named after the event kind (`unlock`, `relock`, ...),
+///   the data being `{event, headline, game, appid, achievement, earned, total, completion}`
+#[derive(Clone)]
+pub struct Server {
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    /// None if there is no address in the setting or it can not be bound
+    pub fn new(setting: &Setting) -> Option<Self> {
+        let address = setting.get_server_address()?;
+        match tiny_http::Server::http(&address) {
+            Ok(server) => {
+                println!("Serve on http://{}/", server.server_addr());
+                Some(Self::start(
+                    server,
+                    setting.get_game_name(),
+                    setting.get_app_id(),
+                ))
+            }
+            Err(e) => {
+                println!("Can not serve on {}: {}", address, e);
+                None
+            }
+        }
+    }
+
+    fn start(server: tiny_http::Server, game: String, appid: u32) -> Self {
+        let slf = Self {
+            state: Arc::new(Mutex::new(State {
+                game,
+                appid,
+                ..Default::default()
+            })),
+        };
+        let handler = slf.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                // event streams stay open, so every request gets its own thread
+                let handler = handler.clone();
+                thread::spawn(move || handler.handle(request));
+            }
+        });
+        slf
+    }
+
+    /// replace the achievements served, call it whenever the table is updated
+    pub fn set_achievements(&self, achievements: Vec<AppAchievement>) {
+        self.state.lock().unwrap().achievements = achievements;
+    }
+
+    fn handle(&self, request: Request) {
+        let url = request.url().to_string();
+        let path = url.split('?').next().unwrap_or_default();
+        let response = if *request.method() != Method::Get {
+            text(405, "Method Not Allowed")
+        } else if path == "/overlay" {
+            Response::from_string(OVERLAY)
+                .with_header(header("Content-Type", "text/html; charset=utf-8"))
+        } else if path == "/api/events" {
+            return self.stream_events(request);
+        } else if path == "/api/achievements" {
+            json(&serde_json::to_value(&self.state.lock().unwrap().achievements).unwrap())
+        } else if path == "/api/completion" {
+            json(&self.completion())
+        } else if let Some(id) = path.strip_prefix("/api/icons/") {
+            self.icon(&percent_decode(id))
+        } else {
+            text(404, "Not Found")
+        };
+        let response = response.with_header(header("Access-Control-Allow-Origin", "*"));
+        if let Err(e) = request.respond(response) {
+            println!("Can not respond to {}: {}", url, e);
+        }
+    }
+
+    fn completion(&self) -> serde_json::Value {
+        let state = self.state.lock().unwrap();
+        let total = state.achievements.len();
+        let earned = state.achievements.iter().filter(|ac| ac.state).count();
+        serde_json::json!({
+            "game": state.game,
+            "appid": state.appid,
+            "earned": earned,
+            "total": total,
+            "completion": percent(earned, total),
+        })
+    }
+
+    /// only icons of known achievements are served, never an arbitrary path
+    fn icon(&self, id: &str) -> Response<Cursor<Vec<u8>>> {
+        let icon = self
+            .state
+            .lock()
+            .unwrap()
+            .achievements
+            .iter()
+            .find(|ac| ac.id == id)
+            .map(|ac| ac.icon.clone());
+        let Some(icon) = icon else {
+            return text(404, "Not Found");
+        };
+        match fs::read(&icon) {
+            Ok(bytes) => Response::from_data(bytes)
+                .with_header(header("Content-Type", content_type(Path::new(&icon)))),
+            Err(e) => {
+                println!("Can not read icon {:?}: {}", icon, e);
+                text(404, "Not Found")
+            }
+        }
+    }
+
+    fn stream_events(&self, request: Request) {
+        let (sender, receiver) = mpsc::channel();
+        self.state.lock().unwrap().subscribers.push(sender);
+        let mut writer = request.into_writer();
+        let head = "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\
+                    Access-Control-Allow-Origin: *\r\n\
+                    Connection: close\r\n\r\n";
+        let mut chunk = head.to_string();
+        loop {
+            if writer
+                .write_all(chunk.as_bytes())
+                .and_then(|_| writer.flush())
+                .is_err()
+            {
+                // the client is gone, dropping the receiver unsubscribes it
+                break;
+            }
+            chunk = match receiver.recv_timeout(KEEP_ALIVE) {
+                Ok(message) => message,
+                Err(mpsc::RecvTimeoutError::Timeout) => ": keep-alive\n\n".to_string(),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+        }
+    }
+
+    /// push the event to every open event stream
+    fn publish(&self, event: &AchievementEvent, headline: &str) {
+        let mut state = self.state.lock().unwrap();
+        let data = serde_json::json!({
+            "event": event.kind.name(),
+            "headline": headline,
+            "game": state.game,
+            "appid": state.appid,
+            "achievement": event.achievement,
+            "earned": event.earned,
+            "total": event.total,
+            "completion": percent(event.earned, event.total),
+        });
+        let message = format!("event: {}\ndata: {}\n\n", event.kind.name(), data);
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.send(message.clone()).is_ok());
+    }
+}
+
+impl Notifier for Server {
+    fn name(&self) -> &'static str {
+        "server"
+    }
+
+    fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String> {
+        self.publish(event, headline);
+        Ok(())
+    }
+}
+
+fn percent(earned: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        earned as f64 * 100.0 / total as f64
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).unwrap()
+}
+
+fn text(status: u16, body: &str) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(body).with_status_code(status)
+}
+
+fn json(value: &serde_json::Value) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(value.to_string())
+        .with_header(header("Content-Type", "application/json; charset=utf-8"))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// decode the `%XX` escapes of an url path segment
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[test]
+fn serve_completion_and_events() {
+    use std::io::{BufRead, BufReader};
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap();
+    let server = Server::start(server, "Game".to_string(), 480);
+    let mut achievements = vec![AppAchievement::default(); 4];
+    achievements[0].id = "ACH WIN".to_string();
+    achievements[0].state = true;
+    server.set_achievements(achievements);
+
+    let completion = ureq::get(&format!("http://{}/api/completion", address))
+        .call()
+        .unwrap()
+        .into_string()
+        .unwrap();
+    let completion: serde_json::Value = serde_json::from_str(&completion).unwrap();
+    assert_eq!(completion["earned"], 1);
+    assert_eq!(completion["completion"], 25.0);
+    assert_eq!(percent_decode("ACH%20WIN%2"), "ACH WIN%2");
+
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    stream
+        .write_all(b"GET /api/events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    // wait for the headers, the stream is subscribed by then
+    while line != "\r\n" {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+    }
+    let mut event = AchievementEvent::default();
+    event.achievement.id = "ACH WIN".to_string();
+    server.notify(&event, "Gained").unwrap();
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "event: unlock\n");
+    line.clear();
+    reader.read_line(&mut line).unwrap();
+    let data: serde_json::Value =
+        serde_json::from_str(line.strip_prefix("data: ").unwrap()).unwrap();
+    assert_eq!(data["achievement"]["id"], "ACH WIN");
+    assert_eq!(data["headline"], "Gained");
+}
@@ -1,4 +1,4 @@
-use std::fs;
+use std::{collections::HashMap, fs};
 
 use serde::{Deserialize, Serialize};
 
@@ -22,10 +22,18 @@ struct Args {
     /// Path of the setting file. If not provided, it will use the default directory %APPDATA%/Goldberg SteamEmu Saves/achievement_reminder_setting.json.
     #[arg(short, long)]
     settingpath: Option<String>,
-    /// Default languages list of the achievements. If not provided, it will use the list ["schinese", "tchinese", "chinese", "english", "japanese", "french"].
+    /// Default languages list of the achievements. If not provided, it will use the language of the system followed by ["schinese", "tchinese", "english", "japanese", "french"].
     /// The display language will be the first language that can be found in the list.
+    /// Both Steam language codes (schinese, brazilian) and locales (zh_CN, pt_BR) are accepted.
     #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
     languages: Option<Vec<String>>,
+    /// Import global unlock percents of the achievements, kept for the appid afterwards.
+    /// Accepts the saved Steam Web API GetGlobalAchievementPercentagesForApp response,
+    /// a JSON object of name to percent, or CSV like the SteamDB export.
+    #[arg(short, long)]
+    rarity: Option<String>,
+    #[command(subcommand)]
+    command: Option<crate::cli::Command>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -33,13 +41,37 @@ pub struct Setting {
     darkmode: Option<bool>,
     fonts: Option<Vec<String>>,
     languages: Option<Vec<String>>,
+    ui_language: Option<String>,
+    secondary_language: Option<String>,
     app_data_path: Option<String>,
     setting_dir: Option<String>,
     goldberg_path: Option<String>,
     image_dir: Option<String>,
+    icon_search_dirs: Option<Vec<String>>,
+    icon_base_url: Option<String>,
 
+    game_name: Option<String>,
     pop_up_time: Option<f32>,
     achievement_window_size: Option<(f32, f32)>,
+    gallery_tile_size: Option<f32>,
+
+    sound_pack_dir: Option<String>,
+    sounds: Option<HashMap<String, Vec<String>>>,
+    volume: Option<f32>,
+    mute: Option<bool>,
+    audio_device: Option<String>,
+
+    notifiers: Option<Vec<String>>,
+    webhooks: Option<Vec<crate::webhook::Webhook>>,
+    server_address: Option<String>,
+
+    tts: Option<bool>,
+    tts_command: Option<String>,
+    tts_template: Option<String>,
+    tts_voice: Option<String>,
+
+    tray: Option<bool>,
+    start_in_tray: Option<bool>,
 
     #[serde(skip)]
     args: Args,
@@ -81,22 +113,36 @@ impl Setting {
         "新宋体",
     ];
 
-    pub const DEFAULT_LANGUAGES: [&str; 6] = [
-        "schinese", "tchinese", "chinese", "english", "japanese", "french",
-    ];
+    pub const DEFAULT_LANGUAGES: [&str; 5] =
+        ["schinese", "tchinese", "english", "japanese", "french"];
 
     const DEFAULT_APP_ID_PATH_1: &str = "ColdClientLoader.ini";
     const DEFAULT_IMAGE_DIR: &str = "steam_settings/achievement_images/";
+    // releative to the parent of the image dir
+    const SECONDARY_IMAGE_DIR_NAME: &str = "img/";
     const DEFAULT_APP_ID_PATH_2: &str = "steam_settings/steam_appid.txt";
     const DEFAULT_ACHIEVEMENTS_DATA_PATH: &str = "steam_settings/achievements.json";
     // releative to goldberg_path/Appid
     const DEFAULT_GOLDBERG_NAME: &str = "Goldberg SteamEmu Saves/";
     const DEFAULT_SETTING_NAME: &str = "achievement_reminder_setting.json";
     const DEFAULT_ACHIEVEMENTS_NAME: &str = "achievements.json";
+    // releative to the directory of the setting file
+    const DEFAULT_LANGUAGE_PACK_DIR: &str = "languages/";
+    // releative to the directory of the setting file, followed by Appid
+    const DEFAULT_DATA_DIR: &str = "achievement_reminder/";
 
     const DEFAULT_POP_UP_TIME: f32 = 10.0;
     const DEFAULT_ACHIEVEMENT_WINDOW_SIZE: (f32, f32) = (500.0, 150.0);
+    const DEFAULT_GALLERY_TILE_SIZE: f32 = 64.0;
     const DEFAULT_DARK_MODE: bool = false;
+    const DEFAULT_VOLUME: f32 = 1.0;
+    const DEFAULT_MUTE: bool = false;
+    const DEFAULT_NOTIFIERS: [&str; 1] = [crate::notifier::POPUP];
+    const DEFAULT_TTS: bool = false;
+    const DEFAULT_TTS_COMMAND: &str = "espeak-ng -v {voice} {text}";
+    const DEFAULT_TTS_TEMPLATE: &str = "{title}. {description}";
+    const DEFAULT_TRAY: bool = true;
+    const DEFAULT_START_IN_TRAY: bool = false;
 
     fn get_default_app_data_path() -> String {
         dirs::data_dir()
@@ -109,6 +155,20 @@ impl Setting {
         // "AppData/".into()
     }
 
+    /// the system language first, then DEFAULT_LANGUAGES
+    fn get_default_languages() -> Vec<String> {
+        let mut languages: Vec<String> = vec![];
+        for language in crate::language::system_language()
+            .into_iter()
+            .chain(Self::DEFAULT_LANGUAGES)
+        {
+            if !languages.iter().any(|l| l == language) {
+                languages.push(language.to_string());
+            }
+        }
+        languages
+    }
+
     fn get_default_goldberg_path() -> String {
         Self::get_default_app_data_path() + Self::DEFAULT_GOLDBERG_NAME
     }
@@ -131,19 +191,39 @@ impl Default for Setting {
         Self {
             darkmode: Some(Self::DEFAULT_DARK_MODE),
             fonts: Some(Self::DEFAULT_FONTS.iter().map(|s| s.to_string()).collect()),
-            languages: Some(
-                Self::DEFAULT_LANGUAGES
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect(),
-            ),
+            languages: Some(Self::get_default_languages()),
+            ui_language: None,
+            secondary_language: None,
             app_data_path: Some(Self::get_default_app_data_path()),
             goldberg_path: Some(Self::get_default_goldberg_path()),
             setting_dir: Some(Self::get_default_setting_path()),
             image_dir: Some(Self::DEFAULT_IMAGE_DIR.to_string()),
+            icon_search_dirs: None,
+            icon_base_url: None,
             args: Default::default(),
+            game_name: None,
             pop_up_time: Some(Self::DEFAULT_POP_UP_TIME),
             achievement_window_size: Some(Self::DEFAULT_ACHIEVEMENT_WINDOW_SIZE),
+            gallery_tile_size: Some(Self::DEFAULT_GALLERY_TILE_SIZE),
+            sound_pack_dir: None,
+            sounds: None,
+            volume: Some(Self::DEFAULT_VOLUME),
+            mute: Some(Self::DEFAULT_MUTE),
+            audio_device: None,
+            notifiers: Some(
+                Self::DEFAULT_NOTIFIERS
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            ),
+            webhooks: None,
+            server_address: None,
+            tts: Some(Self::DEFAULT_TTS),
+            tts_command: Some(Self::DEFAULT_TTS_COMMAND.to_string()),
+            tts_template: Some(Self::DEFAULT_TTS_TEMPLATE.to_string()),
+            tts_voice: None,
+            tray: Some(Self::DEFAULT_TRAY),
+            start_in_tray: Some(Self::DEFAULT_START_IN_TRAY),
         }
     }
 }
@@ -168,10 +248,25 @@ impl Setting {
         println!("Json File {:#?}", self);
         println!("Setting.get_fonts {:#?}", self.get_fonts());
         println!("Setting.get_languages {:#?}", self.get_languages());
+        println!("Setting.get_ui_language {:#?}", self.get_ui_language());
+        println!(
+            "Setting.get_secondary_language {:#?}",
+            self.get_secondary_language()
+        );
         println!("Setting.get_app_data_path {:#?}", self.get_app_data_path());
         println!("Setting.get_setting_path {:#?}", self.get_setting_path());
+        println!(
+            "Setting.get_language_pack_dir {:#?}",
+            self.get_language_pack_dir()
+        );
         println!("Setting.get_image_dir {:#?}", self.get_image_dir());
+        println!(
+            "Setting.get_icon_search_dirs {:#?}",
+            self.get_icon_search_dirs()
+        );
         println!("Setting.get_app_id {:#?}", self.get_app_id());
+        println!("Setting.get_game_name {:#?}", self.get_game_name());
+        println!("Setting.get_data_dir {:#?}", self.get_data_dir());
         println!("Setting.get_goldberg_path {:#?}", self.get_goldberg_path());
         println!(
             "Setting.get_achievement_data_path {:#?}",
@@ -191,19 +286,55 @@ impl Setting {
         }
     }
 
+    /// Preferred languages as Steam language codes.
+    /// Locales are mapped to Steam codes and unknown entries are dropped.
     pub fn get_languages(&self) -> Vec<String> {
-        if let Some(languages) = &self.args.languages {
+        let languages = if let Some(languages) = &self.args.languages {
             languages.clone()
         } else if let Some(languages) = &self.languages {
             languages.clone()
         } else {
-            Self::DEFAULT_LANGUAGES
-                .iter()
-                .map(|s| s.to_string())
-                .collect()
+            Self::get_default_languages()
+        };
+        let mut res: Vec<String> = vec![];
+        for language in languages {
+            match crate::language::normalize(&language) {
+                Some(code) if !res.iter().any(|l| l == code) => res.push(code.to_string()),
+                Some(_) => {}
+                None => println!("Unknown language {:?} is ignored", language),
+            }
+        }
+        res
+    }
+
+    /// language of the UI chrome, defaults to the first display language
+    pub fn get_ui_language(&self) -> String {
+        if let Some(language) = &self.ui_language {
+            language.clone()
+        } else {
+            self.get_languages()
+                .into_iter()
+                .next()
+                .unwrap_or("english".to_string())
         }
     }
 
+    pub fn set_ui_language(&mut self, language: &str) {
+        self.ui_language = Some(language.to_string());
+    }
+
+    /// language shown under the display language, None to show only one
+    pub fn get_secondary_language(&self) -> Option<String> {
+        self.secondary_language
+            .as_deref()
+            .and_then(crate::language::normalize)
+            .map(|s| s.to_string())
+    }
+
+    pub fn set_secondary_language(&mut self, language: Option<&str>) {
+        self.secondary_language = language.map(|s| s.to_string());
+    }
+
     pub fn get_app_data_path(&self) -> String {
         if let Some(path) = &self.app_data_path {
             path.clone()
@@ -220,6 +351,30 @@ impl Setting {
         }
     }
 
+    /// the setting file in use, given by `--settingpath` or the default one
+    pub fn get_setting_file(&self) -> String {
+        if let Some(sp) = &self.args.settingpath {
+            sp.clone()
+        } else {
+            self.get_setting_path()
+        }
+    }
+
+    /// directory containing the setting file
+    pub fn get_setting_dir(&self) -> String {
+        let path = self.get_setting_file();
+        match std::path::Path::new(&path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => {
+                dir.as_os_str().to_str().unwrap().to_string() + "/"
+            }
+            _ => "./".to_string(),
+        }
+    }
+
+    pub fn get_language_pack_dir(&self) -> String {
+        self.get_setting_dir() + Self::DEFAULT_LANGUAGE_PACK_DIR
+    }
+
     pub fn get_image_dir(&self) -> String {
         if let Some(path) = &self.image_dir {
             path.clone()
@@ -228,6 +383,24 @@ impl Setting {
         }
     }
 
+    /// directories searched for icon files: the image dir, the img/ dir next to it
+    /// (steam_settings/img/ by default), then the extra ones of `icon_search_dirs`
+    pub fn get_icon_search_dirs(&self) -> Vec<String> {
+        let image_dir = self.get_image_dir();
+        let secondary = std::path::Path::new(&image_dir)
+            .parent()
+            .unwrap_or(std::path::Path::new(""))
+            .join(Self::SECONDARY_IMAGE_DIR_NAME);
+        let mut dirs = vec![image_dir, secondary.to_string_lossy().to_string()];
+        dirs.extend(self.icon_search_dirs.clone().unwrap_or_default());
+        dirs
+    }
+
+    /// where `fetch-icons` downloads icons from, like a local mirror, None for the Steam CDN
+    pub fn get_icon_base_url(&self) -> Option<String> {
+        self.icon_base_url.clone()
+    }
+
     pub fn get_app_id(&self) -> u32 {
         if let Some(id) = self.args.appid {
             return id;
@@ -248,6 +421,50 @@ impl Setting {
         panic!("Can not find app id from files or command line!")
     }
 
+    /// Name of the game: the setting, or the executable in ColdClientLoader.ini, or 'App {appid}'
+    pub fn get_game_name(&self) -> String {
+        if let Some(name) = &self.game_name {
+            return name.clone();
+        }
+        if let Ok(s) = fs::read_to_string(Self::DEFAULT_APP_ID_PATH_1) {
+            if let Some(name) = ini::macro_safe_read(&s)
+                .ok()
+                .and_then(|f| f.get("steamclient")?.get("exe")?.to_owned())
+                .and_then(|exe| {
+                    let stem = std::path::Path::new(&exe)
+                        .file_stem()?
+                        .to_str()?
+                        .to_string();
+                    Some(stem).filter(|s| !s.is_empty())
+                })
+            {
+                return name;
+            }
+        }
+        format!("App {}", self.get_app_id())
+    }
+
+    /// task to run instead of opening the window
+    pub fn get_command(&self) -> Option<&crate::cli::Command> {
+        self.args.command.as_ref()
+    }
+
+    /// rarity file to import, given by `--rarity`
+    pub fn get_rarity_import(&self) -> Option<String> {
+        self.args.rarity.clone()
+    }
+
+    /// directory for the data of the reminder itself, one dir per appid inside
+    pub fn get_reminder_dir(&self) -> String {
+        self.get_setting_dir() + Self::DEFAULT_DATA_DIR
+    }
+
+    /// directory for the data of the reminder itself about the current game,
+    /// like '{setting dir}/achievement_reminder/{appid}/'
+    pub fn get_data_dir(&self) -> String {
+        format!("{}{}/", self.get_reminder_dir(), self.get_app_id())
+    }
+
     pub fn get_goldberg_path(&self) -> String {
         if let Some(path) = &self.goldberg_path {
             path.clone()
@@ -293,6 +510,138 @@ impl Setting {
         }
     }
 
+    /// side of the icons in the gallery view
+    pub fn get_gallery_tile_size(&self) -> f32 {
+        if let Some(size) = self.gallery_tile_size {
+            size
+        } else {
+            Self::DEFAULT_GALLERY_TILE_SIZE
+        }
+    }
+
+    pub fn set_gallery_tile_size(&mut self, size: f32) {
+        self.gallery_tile_size = Some(size);
+    }
+
+    /// directory of a custom sound pack, containing files like `unlock.ogg`
+    pub fn get_sound_pack_dir(&self) -> Option<String> {
+        self.sound_pack_dir.clone()
+    }
+
+    /// event name -> sounds to play, like {"unlock": ["sound3", "fanfare.wav"]}
+    pub fn get_sounds(&self) -> HashMap<String, Vec<String>> {
+        self.sounds.clone().unwrap_or_default()
+    }
+
+    pub fn get_volume(&self) -> f32 {
+        if let Some(volume) = self.volume {
+            volume
+        } else {
+            Self::DEFAULT_VOLUME
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = Some(volume);
+    }
+
+    pub fn get_mute(&self) -> bool {
+        if let Some(mute) = self.mute {
+            mute
+        } else {
+            Self::DEFAULT_MUTE
+        }
+    }
+
+    pub fn set_mute(&mut self, mute: bool) {
+        self.mute = Some(mute);
+    }
+
+    /// name of the audio output device, None for the system default
+    pub fn get_audio_device(&self) -> Option<String> {
+        self.audio_device.clone()
+    }
+
+    pub fn set_audio_device(&mut self, device: Option<String>) {
+        self.audio_device = device;
+    }
+
+    /// how achievements are shown: "popup", "freedesktop" (Linux), "toast" (Windows)
+    pub fn get_notifiers(&self) -> Vec<String> {
+        if let Some(notifiers) = &self.notifiers {
+            notifiers.clone()
+        } else {
+            Self::DEFAULT_NOTIFIERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        }
+    }
+
+    /// webhooks receiving every achievement event
+    pub fn get_webhooks(&self) -> Vec<crate::webhook::Webhook> {
+        self.webhooks.clone().unwrap_or_default()
+    }
+
+    /// address of the local server for overlays, like "127.0.0.1:8080", None to not start it
+    pub fn get_server_address(&self) -> Option<String> {
+        self.server_address.clone()
+    }
+
+    /// whether achievements are also announced by speech
+    pub fn get_tts(&self) -> bool {
+        if let Some(tts) = self.tts {
+            tts
+        } else {
+            Self::DEFAULT_TTS
+        }
+    }
+
+    pub fn set_tts(&mut self, tts: bool) {
+        self.tts = Some(tts);
+    }
+
+    /// command run to speak, with `{voice}` and `{text}` placeholders
+    pub fn get_tts_command(&self) -> String {
+        if let Some(command) = &self.tts_command {
+            command.clone()
+        } else {
+            Self::DEFAULT_TTS_COMMAND.to_string()
+        }
+    }
+
+    /// text spoken for an achievement, see `Speaker::render` for the placeholders
+    pub fn get_tts_template(&self) -> String {
+        if let Some(template) = &self.tts_template {
+            template.clone()
+        } else {
+            Self::DEFAULT_TTS_TEMPLATE.to_string()
+        }
+    }
+
+    /// voice passed to the command, None to follow the display language
+    pub fn get_tts_voice(&self) -> Option<String> {
+        self.tts_voice.clone()
+    }
+
+    /// whether the tray icon is shown
+    pub fn get_tray(&self) -> bool {
+        if let Some(tray) = self.tray {
+            tray
+        } else {
+            Self::DEFAULT_TRAY
+        }
+    }
+
+    /// start as the hidden reminder, out of the taskbar, with only the tray icon to open it
+    pub fn get_start_in_tray(&self) -> bool {
+        if let Some(start) = self.start_in_tray {
+            start
+        } else {
+            Self::DEFAULT_START_IN_TRAY
+        }
+    }
+
     pub fn get_dark_mode(&self) -> bool {
         if let Some(b) = self.darkmode {
             b
@@ -322,3 +671,18 @@ fn write_sample_setting() {
     )
     .unwrap();
 }
+
+#[test]
+fn secondary_image_dir_follows_image_dir() {
+    let mut set = Setting::default();
+    assert_eq!(
+        set.get_icon_search_dirs(),
+        ["steam_settings/achievement_images/", "steam_settings/img/"]
+    );
+    set.image_dir = Some("game/settings/images".to_string());
+    set.icon_search_dirs = Some(vec!["icons".to_string()]);
+    assert_eq!(
+        set.get_icon_search_dirs(),
+        ["game/settings/images", "game/settings/img/", "icons"]
+    );
+}
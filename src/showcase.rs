@@ -0,0 +1,346 @@
+use ab_glyph::{Font, FontVec, ScaleFont};
+use image::{imageops, Rgba, RgbaImage};
+
+use crate::{
+    achievement::AppAchievement,
+    fonts, icon_cache, rarity,
+    statistics::{self, Statistics},
+};
+
+const BACKGROUND: Rgba<u8> = Rgba([27, 27, 27, 255]);
+const TEXT: Rgba<u8> = Rgba([230, 230, 230, 255]);
+const TITLE: Rgba<u8> = Rgba([173, 216, 230, 255]);
+const WEAK: Rgba<u8> = Rgba([160, 160, 160, 255]);
+const GAINED: Rgba<u8> = Rgba([144, 238, 144, 255]);
+const LOST: Rgba<u8> = Rgba([255, 165, 0, 255]);
+pub const GOLD: Rgba<u8> = Rgba([255, 215, 0, 255]);
+const SILVER: Rgba<u8> = Rgba([160, 170, 185, 255]);
+
+const PADDING: u32 = 24;
+const CARD_WIDTH: u32 = 900;
+const CARD_ICON: u32 = 180;
+const GRID_COLUMNS: u32 = 10;
+const GRID_ICON: u32 = 64;
+const GRID_GAP: u32 = 8;
+
+/// Draws text with the fonts of the UI, each character with the first font having it
+pub struct TextRenderer {
+    fonts: Vec<FontVec>,
+}
+
+impl TextRenderer {
+    /// `fonts` are the family names of the setting, after the builtin ones
+    pub fn new(fonts: &[String]) -> Self {
+        let fonts = fonts::load_font_data(fonts)
+            .into_iter()
+            .filter_map(|(bytes, index)| FontVec::try_from_vec_and_index(bytes, index).ok())
+            .collect();
+        Self { fonts }
+    }
+
+    fn font_for(&self, c: char) -> Option<&FontVec> {
+        self.fonts
+            .iter()
+            .find(|font| font.glyph_id(c).0 != 0)
+            .or(self.fonts.first())
+    }
+
+    /// width of one line in pixels
+    pub fn width(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .filter_map(|c| {
+                let font = self.font_for(c)?;
+                Some(font.as_scaled(size).h_advance(font.glyph_id(c)))
+            })
+            .sum()
+    }
+
+    /// height taken by one line
+    pub fn line_height(&self, size: f32) -> f32 {
+        match self.fonts.first() {
+            Some(font) => {
+                let scaled = font.as_scaled(size);
+                scaled.height() + scaled.line_gap()
+            }
+            None => size,
+        }
+    }
+
+    /// draw one line with its top at y
+    pub fn draw(
+        &self,
+        image: &mut RgbaImage,
+        text: &str,
+        x: f32,
+        y: f32,
+        size: f32,
+        color: Rgba<u8>,
+    ) {
+        let Some(primary) = self.fonts.first() else {
+            return;
+        };
+        let baseline = y + primary.as_scaled(size).ascent();
+        let mut caret = x;
+        for c in text.chars() {
+            let Some(font) = self.font_for(c) else {
+                continue;
+            };
+            let id = font.glyph_id(c);
+            let glyph = id.with_scale_and_position(size, ab_glyph::point(caret, baseline));
+            caret += font.as_scaled(size).h_advance(id);
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i64 + gx as i64;
+                let py = bounds.min.y as i64 + gy as i64;
+                if px >= 0 && py >= 0 && px < image.width() as i64 && py < image.height() as i64 {
+                    blend(image.get_pixel_mut(px as u32, py as u32), color, coverage);
+                }
+            });
+        }
+    }
+
+    /// split the text in lines not wider than max_width, breaking between words or CJK characters
+    pub fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let mut lines = vec![];
+        for paragraph in text.lines() {
+            let mut line = String::new();
+            for token in tokens(paragraph) {
+                let candidate = format!("{}{}", line, token);
+                if !line.is_empty() && self.width(&candidate, size) > max_width {
+                    lines.push(line);
+                    line = token.trim_start().to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// words with their leading spaces, and wide characters one by one
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut token = String::new();
+    for c in text.chars() {
+        let wide = c >= '\u{2E80}';
+        let word_start = c.is_whitespace() && token.chars().any(|t| !t.is_whitespace());
+        if (wide || word_start) && !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+        token.push(c);
+        if wide {
+            tokens.push(std::mem::take(&mut token));
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+    let alpha = coverage.clamp(0.0, 1.0) * color[3] as f32 / 255.0;
+    for i in 0..3 {
+        pixel[i] = (pixel[i] as f32 * (1.0 - alpha) + color[i] as f32 * alpha).round() as u8;
+    }
+    pixel[3] = pixel[3].max((alpha * 255.0) as u8);
+}
+
+/// the icon resized to a square, or a placeholder when it can not be read
+fn icon(text: &TextRenderer, ac: &AppAchievement, size: u32) -> RgbaImage {
+    match image::open(&ac.icon) {
+        Ok(img) => imageops::resize(&img.to_rgba8(), size, size, imageops::FilterType::Triangle),
+        Err(e) => {
+            println!("Can not open icon {:?}: {}", ac.icon, e);
+            placeholder(text, ac, size)
+        }
+    }
+}
+
+/// the initials of the title on a colored tile, for an achievement without a usable icon
+pub fn placeholder(text: &TextRenderer, ac: &AppAchievement, size: u32) -> RgbaImage {
+    let [r, g, b] = icon_cache::placeholder_color(&ac.id, ac.state);
+    let mut image = RgbaImage::from_pixel(size, size, Rgba([r, g, b, 255]));
+    let initials = icon_cache::initials(&ac.title);
+    let font_size = size as f32 * 0.4;
+    let x = (size as f32 - text.width(&initials, font_size)) / 2.0;
+    let y = (size as f32 - text.line_height(font_size)) / 2.0;
+    text.draw(&mut image, &initials, x, y, font_size, TEXT);
+    image
+}
+
+fn draw_border(image: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32, width: u32, color: Rgba<u8>) {
+    for py in y..(y + h).min(image.height()) {
+        for px in x..(x + w).min(image.width()) {
+            let inside =
+                px >= x + width && px + width < x + w && py >= y + width && py + width < y + h;
+            if !inside {
+                image.put_pixel(px, py, color);
+            }
+        }
+    }
+}
+
+/// border color of the rarity tier, None for common achievements
+pub fn tier_color(ac: &AppAchievement) -> Option<Rgba<u8>> {
+    match rarity::Tier::of(ac.rarity?) {
+        rarity::Tier::Rare => Some(GOLD),
+        rarity::Tier::Uncommon => Some(SILVER),
+        rarity::Tier::Common => None,
+    }
+}
+
+/// What a single achievement card shows
+pub struct Card<'a> {
+    pub achievement: &'a AppAchievement,
+    /// the state line, like 'Achievement Gained! CONGRATS!'
+    pub headline: &'a str,
+    /// lines under the date, like the perfect game summary
+    pub note: &'a str,
+    pub border: Option<Rgba<u8>>,
+}
+
+/// a card like the popup: icon on the left; headline, title, description and date on the right
+pub fn render_card(text: &TextRenderer, card: &Card) -> RgbaImage {
+    let ac = card.achievement;
+    let text_x = PADDING * 2 + CARD_ICON;
+    let text_width = (CARD_WIDTH - text_x - PADDING) as f32;
+    let mut lines: Vec<(String, f32, Rgba<u8>)> = vec![];
+    let headline_color = if ac.state { GAINED } else { LOST };
+    lines.push((card.headline.to_string(), 26.0, headline_color));
+    for line in text.wrap(&ac.title, 32.0, text_width) {
+        lines.push((line, 32.0, TITLE));
+    }
+    for line in text.wrap(&ac.description, 22.0, text_width) {
+        lines.push((line, 22.0, WEAK));
+    }
+    if !ac.date.is_empty() {
+        lines.push((ac.date.clone(), 18.0, WEAK));
+    }
+    for line in text.wrap(card.note, 20.0, text_width) {
+        lines.push((line, 20.0, TEXT));
+    }
+    let text_height: f32 = lines
+        .iter()
+        .map(|(_, size, _)| text.line_height(*size))
+        .sum();
+    let height = (PADDING * 2 + CARD_ICON).max(PADDING * 2 + text_height.ceil() as u32);
+    let mut image = RgbaImage::from_pixel(CARD_WIDTH, height, BACKGROUND);
+    let icon = icon(text, ac, CARD_ICON);
+    let icon_y = (height - CARD_ICON) / 2;
+    imageops::overlay(&mut image, &icon, PADDING as i64, icon_y as i64);
+    let mut y = ((height as f32 - text_height) / 2.0).max(PADDING as f32);
+    for (line, size, color) in &lines {
+        text.draw(&mut image, line, text_x as f32, y, *size, *color);
+        y += text.line_height(*size);
+    }
+    if let Some(color) = card.border {
+        draw_border(&mut image, 0, 0, CARD_WIDTH, height, 6, color);
+    }
+    image
+}
+
+/// the game and its completion above the icons of every earned achievement, in unlock order
+pub fn render_grid(text: &TextRenderer, game: &str, achievements: &[AppAchievement]) -> RgbaImage {
+    let stats = Statistics::new(achievements);
+    let mut earned: Vec<&AppAchievement> = achievements.iter().filter(|ac| ac.state).collect();
+    earned.sort_by_key(|ac| ac.earned_time);
+    let mut header: Vec<(String, f32, Rgba<u8>)> = vec![
+        (game.to_string(), 32.0, TITLE),
+        (
+            format!(
+                "{} / {}  ({:.1}%)",
+                stats.earned,
+                stats.total,
+                stats.completion()
+            ),
+            24.0,
+            if stats.total > 0 && stats.earned == stats.total {
+                GOLD
+            } else {
+                GAINED
+            },
+        ),
+    ];
+    if let (Some(first), Some(latest)) = (stats.first, stats.latest) {
+        header.push((
+            format!(
+                "{} ~ {}",
+                statistics::format_time(first),
+                statistics::format_time(latest)
+            ),
+            18.0,
+            WEAK,
+        ));
+    }
+    let header_height: f32 = header
+        .iter()
+        .map(|(_, size, _)| text.line_height(*size))
+        .sum();
+    let width = PADDING * 2 + GRID_COLUMNS * GRID_ICON + (GRID_COLUMNS - 1) * GRID_GAP;
+    let rows = (earned.len() as u32).div_ceil(GRID_COLUMNS);
+    let grid_top = PADDING * 2 + header_height.ceil() as u32;
+    let height = grid_top + rows * (GRID_ICON + GRID_GAP) + PADDING;
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+    let mut y = PADDING as f32;
+    for (line, size, color) in &header {
+        text.draw(&mut image, line, PADDING as f32, y, *size, *color);
+        y += text.line_height(*size);
+    }
+    for (i, ac) in earned.iter().enumerate() {
+        let x = PADDING + (i as u32 % GRID_COLUMNS) * (GRID_ICON + GRID_GAP);
+        let y = grid_top + (i as u32 / GRID_COLUMNS) * (GRID_ICON + GRID_GAP);
+        imageops::overlay(&mut image, &icon(text, ac, GRID_ICON), x as i64, y as i64);
+        if let Some(color) = tier_color(ac) {
+            draw_border(&mut image, x, y, GRID_ICON, GRID_ICON, 3, color);
+        }
+    }
+    image
+}
+
+pub fn save(image: &RgbaImage, path: &std::path::Path) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    image.save(path).map_err(|e| e.to_string())
+}
+
+#[test]
+fn render_showcase_images() {
+    let text = TextRenderer::new(&crate::setting::Setting::default().get_fonts());
+    assert!(!text.fonts.is_empty());
+    let lines = text.wrap(
+        "Win the game twice in a row",
+        20.0,
+        text.width("Win the game", 20.0),
+    );
+    assert_eq!(lines, ["Win the game", "twice in a row"]);
+    assert_eq!(tokens("达成 all"), ["达", "成", " all"]);
+    let achievements: Vec<AppAchievement> = (0..12)
+        .map(|i| AppAchievement {
+            id: format!("ACH_{}", i),
+            state: i % 2 == 0,
+            earned_time: 1_700_000_000 + i,
+            ..Default::default()
+        })
+        .collect();
+    let grid = render_grid(&text, "Game", &achievements);
+    assert_eq!(grid.width(), PADDING * 2 + 10 * GRID_ICON + 9 * GRID_GAP);
+    // the title is drawn somewhere in the header
+    assert!((0..grid.width()).any(|x| *grid.get_pixel(x, PADDING + 16) != BACKGROUND));
+    let card = render_card(
+        &text,
+        &Card {
+            achievement: &achievements[0],
+            headline: "Achievement Gained!",
+            note: "",
+            border: Some(GOLD),
+        },
+    );
+    assert_eq!(*card.get_pixel(0, 0), GOLD);
+}
@@ -0,0 +1,394 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+use crate::{achievement::EventKind, setting::Setting};
+
+type Clip = Arc<[u8]>;
+
+const BUILTIN: [(&str, &[u8]); 6] = [
+    ("sound1", include_bytes!("../assets/sound1.mp3")),
+    ("sound2", include_bytes!("../assets/sound2.mp3")),
+    ("sound3", include_bytes!("../assets/sound3.mp3")),
+    ("sound4", include_bytes!("../assets/sound4.mp3")),
+    ("sound5", include_bytes!("../assets/sound5.mp3")),
+    ("sound6", include_bytes!("../assets/sound6.mp3")),
+];
+
+/// file extensions probed for `{event}.*` inside a sound pack
+const EXTENSIONS: [&str; 4] = ["mp3", "ogg", "wav", "flac"];
+
+/// how often a missing or non-selected audio device is looked for again
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl EventKind {
+    /// built-in sounds played when neither the setting nor the pack provides one
+    fn default_sounds(&self) -> &'static [&'static str] {
+        match self {
+            EventKind::Unlock => &["sound3", "sound2"],
+            EventKind::Relock => &["sound1", "sound4"],
+            EventKind::RareUnlock => &["sound5", "sound2"],
+            EventKind::Completion => &["sound6"],
+            EventKind::Progress => &["sound3", "sound5"],
+        }
+    }
+}
+
+/// An opened audio device
+struct Output {
+    _stream: rodio::OutputStream,
+    _handle: rodio::OutputStreamHandle,
+    sink: rodio::Sink,
+    device: String,
+}
+
+impl Output {
+    /// open the named device, or the default one for None
+    fn open(device: Option<&str>) -> Result<Self, String> {
+        let host = rodio::cpal::default_host();
+        let device = match device {
+            Some(name) => host
+                .output_devices()
+                .map_err(|e| e.to_string())?
+                .find(|d| d.name().is_ok_and(|n| n == name))
+                .ok_or(format!("Audio device {:?} is not found", name))?,
+            None => host
+                .default_output_device()
+                .ok_or("No audio output device is found".to_string())?,
+        };
+        let name = device.name().unwrap_or_default();
+        let (_stream, _handle) =
+            rodio::OutputStream::try_from_device(&device).map_err(|e| e.to_string())?;
+        let sink = rodio::Sink::try_new(&_handle).map_err(|e| e.to_string())?;
+        println!("Open audio device {:?}", name);
+        Ok(Self {
+            _stream,
+            _handle,
+            sink,
+            device: name,
+        })
+    }
+}
+
+/// What the UI asks the audio thread to do
+enum Command {
+    Play(Vec<Clip>),
+    Volume(f32),
+    /// drop the sounds being played
+    Clear,
+    /// select a device, None for the default one
+    Device(Option<String>),
+}
+
+/// What the audio thread found at a probe
+struct Status {
+    /// the selected device it is about
+    device: Option<String>,
+    devices: Vec<String>,
+    warning: Option<String>,
+}
+
+/// Owner of the audio device, on its own thread: listing and opening devices can take
+/// hundreds of milliseconds on ALSA and WASAPI, and the stream can not leave its thread.
+struct Player {
+    output: Option<Output>,
+    device: Option<String>,
+    devices: Vec<String>,
+    warning: Option<String>,
+    volume: f32,
+    last_probe: Instant,
+}
+
+impl Player {
+    fn run(mut self, commands: mpsc::Receiver<Command>, status: mpsc::Sender<Status>) {
+        self.probe(&status);
+        loop {
+            if self.last_probe.elapsed() >= PROBE_INTERVAL {
+                self.probe(&status);
+            }
+            match commands.recv_timeout(PROBE_INTERVAL.saturating_sub(self.last_probe.elapsed())) {
+                Ok(Command::Play(clips)) => {
+                    let Some(output) = &self.output else {
+                        continue;
+                    };
+                    for clip in clips {
+                        match rodio::Decoder::new(std::io::Cursor::new(clip)) {
+                            Ok(source) => output.sink.append(source),
+                            Err(e) => println!("Can not decode sound: {}", e),
+                        }
+                    }
+                }
+                Ok(Command::Volume(volume)) => {
+                    self.volume = volume;
+                    if let Some(output) = &self.output {
+                        output.sink.set_volume(volume);
+                    }
+                }
+                Ok(Command::Clear) => {
+                    if let Some(output) = &self.output {
+                        output.sink.clear();
+                        output.sink.play();
+                    }
+                }
+                Ok(Command::Device(device)) => {
+                    self.device = device;
+                    self.output = None;
+                    self.probe(&status);
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// (re)open the selected device, falling back to the default one, and report it
+    fn probe(&mut self, status: &mpsc::Sender<Status>) {
+        self.open();
+        let _ = status.send(Status {
+            device: self.device.clone(),
+            devices: self.devices.clone(),
+            warning: self.warning.clone(),
+        });
+    }
+
+    fn open(&mut self) {
+        self.last_probe = Instant::now();
+        self.devices = list_devices();
+        if let Some(output) = &self.output {
+            let wanted = self.device.as_ref().unwrap_or(&output.device);
+            if output.device == *wanted && self.devices.contains(&output.device) {
+                return;
+            }
+        }
+        let opened = match Output::open(self.device.as_deref()) {
+            Ok(output) => Ok(output),
+            Err(e) if self.device.is_some() => match self.output.take() {
+                // keep playing on the fallback device until the selected one shows up
+                Some(output) if self.devices.contains(&output.device) => {
+                    self.output = Some(output);
+                    self.warning = Some(e);
+                    return;
+                }
+                _ => Output::open(None).map_err(|_| e),
+            },
+            Err(e) => Err(e),
+        };
+        match opened {
+            Ok(output) => {
+                output.sink.set_volume(self.volume);
+                if self.device.as_ref().is_some_and(|d| *d != output.device) {
+                    self.warning = Some(format!(
+                        "Audio device {:?} is not found, using {:?}",
+                        self.device.as_ref().unwrap(),
+                        output.device
+                    ));
+                } else {
+                    self.warning = None;
+                }
+                self.output = Some(output);
+            }
+            Err(e) => {
+                if self.warning.as_ref() != Some(&e) {
+                    println!("Sound disabled: {}", e);
+                }
+                self.output = None;
+                self.warning = Some(e);
+            }
+        }
+    }
+}
+
+/// names of the available output devices
+fn list_devices() -> Vec<String> {
+    rodio::cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Sounds played for each event.
+///
+/// For every event the clips are taken from, in order:
+/// 1. the `sounds` entry of the setting, a list of built-in names (`sound1` .. `sound6`)
+///    or files relative to the sound pack directory
+/// 2. `{sound pack dir}/{event}.mp3|ogg|wav|flac`
+/// 3. the built-in default of the event
+///
+/// Without a usable audio device it stays silent and keeps a warning,
+/// the device is looked for again every few seconds.
+pub struct SoundEffects {
+    sender: mpsc::Sender<Command>,
+    status: mpsc::Receiver<Status>,
+    /// device selected in the setting, None for the default one
+    device: Option<String>,
+    devices: Vec<String>,
+    warning: Option<String>,
+    clips: HashMap<EventKind, Vec<Clip>>,
+    mute: bool,
+}
+
+impl SoundEffects {
+    pub fn new(setting: &Setting) -> Self {
+        let (sender, commands) = mpsc::channel();
+        let (status_sender, status) = mpsc::channel();
+        let (device, volume) = (setting.get_audio_device(), setting.get_volume());
+        thread::spawn(move || {
+            let player = Player {
+                output: None,
+                device,
+                devices: vec![],
+                warning: None,
+                volume,
+                last_probe: Instant::now(),
+            };
+            player.run(commands, status_sender)
+        });
+        Self {
+            sender,
+            status,
+            device: setting.get_audio_device(),
+            devices: vec![],
+            warning: None,
+            clips: Self::load_clips(setting),
+            mute: setting.get_mute(),
+        }
+    }
+
+    /// take the result of the last probe of the audio thread, call it every frame
+    pub fn poll(&mut self) {
+        while let Ok(status) = self.status.try_recv() {
+            // a probe of a device selected before is outdated
+            if status.device == self.device {
+                self.devices = status.devices;
+                self.warning = status.warning;
+            }
+        }
+    }
+
+    /// devices found at the last probe
+    pub fn get_devices(&self) -> &[String] {
+        &self.devices
+    }
+
+    pub fn get_device(&self) -> Option<&String> {
+        self.device.as_ref()
+    }
+
+    /// select a device, None for the default one
+    pub fn set_device(&mut self, device: Option<String>) {
+        self.device = device.clone();
+        let _ = self.sender.send(Command::Device(device));
+    }
+
+    /// why sounds are not played on the selected device, if they are not
+    pub fn get_warning(&self) -> Option<&String> {
+        self.warning.as_ref()
+    }
+
+    fn load_clips(setting: &Setting) -> HashMap<EventKind, Vec<Clip>> {
+        let pack_dir = setting.get_sound_pack_dir().map(PathBuf::from);
+        let sounds = setting.get_sounds();
+        let mut clips = HashMap::new();
+        for event in EventKind::ALL {
+            let configured: Option<Vec<Clip>> = sounds.get(event.name()).map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| Self::load_clip(pack_dir.as_ref(), name))
+                    .collect()
+            });
+            let from_pack = || {
+                let dir = pack_dir.as_ref()?;
+                EXTENSIONS
+                    .iter()
+                    .find_map(|ext| Self::read_file(dir.join(format!("{}.{ext}", event.name()))))
+                    .map(|clip| vec![clip])
+            };
+            let list = configured
+                .filter(|list| !list.is_empty())
+                .or_else(from_pack)
+                .unwrap_or_else(|| {
+                    event
+                        .default_sounds()
+                        .iter()
+                        .filter_map(|name| Self::builtin(name))
+                        .collect()
+                });
+            clips.insert(event, list);
+        }
+        clips
+    }
+
+    fn builtin(name: &str) -> Option<Clip> {
+        BUILTIN
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, bytes)| Clip::from(*bytes))
+    }
+
+    /// a built-in name, or a file path (relative to the pack dir if any)
+    fn load_clip(pack_dir: Option<&PathBuf>, name: &str) -> Option<Clip> {
+        Self::builtin(name).or_else(|| {
+            let path = match pack_dir {
+                Some(dir) => dir.join(name),
+                None => PathBuf::from(name),
+            };
+            Self::read_file(path)
+        })
+    }
+
+    fn read_file(path: PathBuf) -> Option<Clip> {
+        let bytes = std::fs::read(&path).ok()?;
+        // make sure it decodes now instead of failing at the first achievement
+        if let Err(e) = rodio::Decoder::new(std::io::Cursor::new(bytes.clone())) {
+            println!("Can not decode sound {:?}: {}", path, e);
+            return None;
+        }
+        println!("Load sound {:?}", path);
+        Some(bytes.into())
+    }
+
+    /// play the sounds of the event, unless muted
+    pub fn play(&self, event: EventKind) {
+        if !self.mute {
+            self.preview(event);
+        }
+    }
+
+    /// play the sounds of the event even when muted
+    pub fn preview(&self, event: EventKind) {
+        let clips = self.clips.get(&event).cloned().unwrap_or_default();
+        let _ = self.sender.send(Command::Play(clips));
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        let _ = self.sender.send(Command::Volume(volume));
+    }
+
+    pub fn set_mute(&mut self, mute: bool) {
+        self.mute = mute;
+        if mute {
+            let _ = self.sender.send(Command::Clear);
+        }
+    }
+}
+
+#[test]
+fn silent_without_device() {
+    let mut sfx = SoundEffects::new(&Setting::default());
+    sfx.set_device(Some("No Such Device".to_string()));
+    // the probe of the default device may come first
+    loop {
+        let status = sfx.status.recv_timeout(Duration::from_secs(30)).unwrap();
+        if status.device == sfx.device {
+            assert!(status.warning.is_some());
+            break;
+        }
+    }
+    sfx.play(EventKind::Unlock);
+}
@@ -0,0 +1,164 @@
+use std::{process::Command, sync::mpsc, thread};
+
+use crate::{achievement::AppAchievement, language, setting::Setting};
+
+/// Something able to say a text aloud.
+/// `speak` may block until the text is said, it runs on a worker thread.
+pub trait SpeechBackend: Send + 'static {
+    fn speak(&self, text: &str, voice: &str) -> Result<(), String>;
+}
+
+/// Run an external program, like `espeak-ng -v {voice} {text}` or `spd-say -l {voice} {text}`.
+///
+/// The template is split on whitespace, then `{voice}` and `{text}` are replaced inside
+/// each argument, so the text is always passed as one argument and never through a shell.
+pub struct CommandBackend {
+    template: Vec<String>,
+}
+
+impl CommandBackend {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.split_whitespace().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn args(&self, text: &str, voice: &str) -> Vec<String> {
+        self.template
+            .iter()
+            .map(|arg| arg.replace("{voice}", voice).replace("{text}", text))
+            .collect()
+    }
+}
+
+impl SpeechBackend for CommandBackend {
+    fn speak(&self, text: &str, voice: &str) -> Result<(), String> {
+        let args = self.args(text, voice);
+        let (program, args) = args.split_first().ok_or("Empty speech command")?;
+        let status = Command::new(program)
+            .args(args)
+            .status()
+            .map_err(|e| format!("Can not run {:?}: {}", program, e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{:?} exits with {}", program, status))
+        }
+    }
+}
+
+/// Says nothing, only sends back what it was asked to say
+#[cfg(test)]
+pub struct NoopBackend {
+    pub spoken: mpsc::Sender<(String, String)>,
+}
+
+#[cfg(test)]
+impl SpeechBackend for NoopBackend {
+    fn speak(&self, text: &str, voice: &str) -> Result<(), String> {
+        self.spoken
+            .send((text.to_string(), voice.to_string()))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Announces achievement events by speech, one after another.
+pub struct Speaker {
+    sender: mpsc::Sender<(String, String)>,
+    template: String,
+    languages: Vec<String>,
+    voice: Option<String>,
+    enabled: bool,
+}
+
+impl Speaker {
+    pub fn new(setting: &Setting) -> Self {
+        Self::with_backend(setting, CommandBackend::new(&setting.get_tts_command()))
+    }
+
+    pub fn with_backend(setting: &Setting, backend: impl SpeechBackend) -> Self {
+        let (sender, receiver) = mpsc::channel::<(String, String)>();
+        thread::spawn(move || {
+            for (text, voice) in receiver {
+                if let Err(e) = backend.speak(&text, &voice) {
+                    println!("Speech failed: {}", e);
+                }
+            }
+        });
+        Self {
+            sender,
+            template: setting.get_tts_template(),
+            languages: setting.get_languages(),
+            voice: setting.get_tts_voice(),
+            enabled: setting.get_tts(),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Fill the template with the achievement.
+    /// Placeholders: `{state}`, `{id}`, `{title}`, `{description}`, `{date}`.
+    pub fn render(&self, ac: &AppAchievement, state: &str) -> String {
+        self.template
+            .replace("{state}", state)
+            .replace("{id}", &ac.id)
+            .replace("{title}", &ac.title)
+            .replace("{description}", &ac.description)
+            .replace("{date}", &ac.date)
+    }
+
+    /// the configured voice, or the one of the language the title is shown in
+    fn voice_for(&self, ac: &AppAchievement) -> String {
+        if let Some(voice) = &self.voice {
+            return voice.clone();
+        }
+        language::pick(&self.languages, &ac.raw.displayName)
+            .map(|(l, _)| l)
+            .or(self.languages.first().map(|l| l.as_str()))
+            .and_then(language::speech_tag)
+            .unwrap_or("en")
+            .to_string()
+    }
+
+    /// queue the achievement to be said, if enabled
+    pub fn announce(&self, ac: &AppAchievement, state: &str) {
+        if self.enabled {
+            let text = self.render(ac, state);
+            let _ = self.sender.send((text, self.voice_for(ac)));
+        }
+    }
+}
+
+#[test]
+fn announce_with_noop_backend() {
+    let (sender, spoken) = mpsc::channel();
+    let backend = NoopBackend { spoken: sender };
+    let mut speaker = Speaker::with_backend(&Setting::default(), backend);
+    speaker.set_enabled(true);
+    speaker.template = "{state} {title}: {description}".to_string();
+    let raw = crate::achievement::AchievementRaw {
+        displayName: [("japanese".to_string(), "称号".to_string())].into(),
+        ..Default::default()
+    };
+    let ac = AppAchievement {
+        id: "ACH_1".to_string(),
+        state: true,
+        title: "称号".to_string(),
+        description: "説明".to_string(),
+        raw,
+        ..Default::default()
+    };
+    speaker.announce(&ac, "Unlocked");
+    assert_eq!(
+        spoken
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .unwrap(),
+        ("Unlocked 称号: 説明".to_string(), "ja".to_string())
+    );
+    assert_eq!(
+        CommandBackend::new("espeak-ng -v {voice} {text}").args("a b", "en"),
+        ["espeak-ng", "-v", "en", "a b"]
+    );
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::achievement::AppAchievement;
+
+/// Completion numbers of a game, shown above the table
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Statistics {
+    pub earned: usize,
+    pub total: usize,
+    pub hidden_earned: usize,
+    pub hidden_total: usize,
+    /// earned time of the first unlock, in seconds since the unix epoch
+    pub first: Option<u64>,
+    /// earned time of the latest unlock, in seconds since the unix epoch
+    pub latest: Option<u64>,
+    /// the two unlocks with the longest time between them
+    pub longest_gap: Option<(u64, u64)>,
+    /// average seconds between two following unlocks
+    pub average_gap: Option<u64>,
+    /// unlocks of every local day from the first unlock to the latest one, empty days included
+    pub per_day: Vec<(NaiveDate, usize)>,
+}
+
+impl Statistics {
+    pub fn new(achievements: &[AppAchievement]) -> Self {
+        let mut times: Vec<u64> = achievements
+            .iter()
+            .filter(|ac| ac.state && ac.earned_time > 0)
+            .map(|ac| ac.earned_time)
+            .collect();
+        times.sort();
+        let longest_gap = times
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .max_by_key(|(from, to)| to - from);
+        let average_gap = (times.len() >= 2)
+            .then(|| (times[times.len() - 1] - times[0]) / (times.len() as u64 - 1));
+        let mut per_day = vec![];
+        if let (Some(first), Some(latest)) = (times.first(), times.last()) {
+            let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+            for time in &times {
+                *counts.entry(local_date(*time)).or_default() += 1;
+            }
+            let last = local_date(*latest);
+            let mut day = local_date(*first);
+            while day <= last {
+                per_day.push((day, counts.get(&day).copied().unwrap_or_default()));
+                day = day.succ_opt().unwrap();
+            }
+        }
+        Self {
+            earned: achievements.iter().filter(|ac| ac.state).count(),
+            total: achievements.len(),
+            hidden_earned: achievements
+                .iter()
+                .filter(|ac| !ac.visibility && ac.state)
+                .count(),
+            hidden_total: achievements.iter().filter(|ac| !ac.visibility).count(),
+            first: times.first().copied(),
+            latest: times.last().copied(),
+            longest_gap,
+            average_gap,
+            per_day,
+        }
+    }
+
+    /// earned achievements in percent
+    pub fn completion(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.earned as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+fn local_time(time: u64) -> DateTime<Local> {
+    DateTime::from_timestamp(time as i64, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+}
+
+pub fn local_date(time: u64) -> NaiveDate {
+    local_time(time).date_naive()
+}
+
+/// like '2021-08-19 12:00:00', the same as the table
+pub fn format_time(time: u64) -> String {
+    local_time(time).format("%Y-%m-%d %T").to_string()
+}
+
+/// like '3d 4h', '5h 12m', '7m 3s'
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, secs % 60)
+    }
+}
+
+#[test]
+fn statistics_of_unlocks() {
+    let start = 1_700_000_000;
+    let earned = |time: u64, visibility: bool| AppAchievement {
+        state: true,
+        earned_time: time,
+        visibility,
+        ..Default::default()
+    };
+    let achievements = [
+        earned(start + 3 * 86400, true),
+        earned(start, false),
+        earned(start + 3600, true),
+        AppAchievement::default(),
+    ];
+    let stats = Statistics::new(&achievements);
+    assert_eq!((stats.earned, stats.total), (3, 4));
+    assert_eq!((stats.hidden_earned, stats.hidden_total), (1, 2));
+    assert_eq!(stats.first, Some(start));
+    assert_eq!(stats.longest_gap, Some((start + 3600, start + 3 * 86400)));
+    assert_eq!(stats.average_gap, Some(3 * 86400 / 2));
+    assert_eq!(stats.per_day.len(), 4);
+    assert_eq!(stats.per_day.iter().map(|(_, n)| n).sum::<usize>(), 3);
+    assert_eq!(format_duration(3 * 86400 + 4 * 3600 + 59), "3d 4h");
+}
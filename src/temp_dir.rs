@@ -0,0 +1,33 @@
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// A fresh directory under the system temp dir for a test, deleted with its content on drop
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps apart the directories of the tests running at the same time
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("achievement_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use crate::games::Game;
+
+/// What a click on the tray icon or its menu asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// bring the main window back
+    Open,
+    TogglePopup,
+    ToggleMute,
+    SwitchGame(u32),
+    Quit,
+}
+
+impl Action {
+    /// id of the menu item
+    #[cfg_attr(not(windows), allow(unused))]
+    fn id(&self) -> String {
+        match self {
+            Action::Open => "open".to_string(),
+            Action::TogglePopup => "popup".to_string(),
+            Action::ToggleMute => "mute".to_string(),
+            Action::SwitchGame(appid) => format!("game:{}", appid),
+            Action::Quit => "quit".to_string(),
+        }
+    }
+
+    #[cfg_attr(not(windows), allow(unused))]
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "open" => Some(Action::Open),
+            "popup" => Some(Action::TogglePopup),
+            "mute" => Some(Action::ToggleMute),
+            "quit" => Some(Action::Quit),
+            _ => id
+                .strip_prefix("game:")?
+                .parse()
+                .ok()
+                .map(Action::SwitchGame),
+        }
+    }
+}
+
+/// Texts and state shown by the tray, the menu is rebuilt when it changes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Menu {
+    /// tooltip, the name of the game
+    pub title: String,
+    pub open: String,
+    pub popup: String,
+    pub mute: String,
+    pub switch_game: String,
+    pub quit: String,
+    pub popup_on: bool,
+    pub muted: bool,
+    /// the game of this run, checked in the list
+    pub appid: u32,
+    pub games: Vec<Game>,
+}
+
+/// called on the tray thread for every action
+pub type Callback = Arc<dyn Fn(Action) + Send + Sync>;
+
+/// The icon in the StatusNotifierItem host on Linux, or in the notification area on Windows
+pub struct Tray {
+    handle: platform::Handle,
+    menu: Menu,
+}
+
+impl Tray {
+    /// None when the system has no tray, the reason is printed
+    pub fn new(menu: Menu, on_action: Callback) -> Option<Self> {
+        match platform::spawn(&menu, on_action) {
+            Ok(handle) => Some(Self { handle, menu }),
+            Err(e) => {
+                println!("Can not show the tray icon: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn update(&mut self, menu: Menu) {
+        if menu != self.menu {
+            self.handle.update(&menu);
+            self.menu = menu;
+        }
+    }
+}
+
+const ICON_SIZE: u32 = 32;
+
+/// a gold medal on a dark ring, as RGBA
+fn icon_rgba() -> Vec<u8> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            rgba.extend_from_slice(&match distance {
+                d if d < 11.0 => [255, 200, 40, 255],
+                d if d < 15.5 => [60, 45, 20, 255],
+                _ => [0, 0, 0, 0],
+            });
+        }
+    }
+    rgba
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use ksni::blocking::TrayMethods;
+
+    use super::{Action, Callback, Menu};
+
+    struct SniTray {
+        menu: Menu,
+        on_action: Callback,
+    }
+
+    impl ksni::Tray for SniTray {
+        fn id(&self) -> String {
+            "achievements_reminder".to_string()
+        }
+
+        fn title(&self) -> String {
+            self.menu.title.clone()
+        }
+
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            // ARGB in network byte order
+            let data = super::icon_rgba()
+                .chunks(4)
+                .flat_map(|p| [p[3], p[0], p[1], p[2]])
+                .collect();
+            vec![ksni::Icon {
+                width: super::ICON_SIZE as i32,
+                height: super::ICON_SIZE as i32,
+                data,
+            }]
+        }
+
+        fn tool_tip(&self) -> ksni::ToolTip {
+            ksni::ToolTip {
+                title: self.menu.title.clone(),
+                ..Default::default()
+            }
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            (self.on_action)(Action::Open);
+        }
+
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            use ksni::menu::{CheckmarkItem, StandardItem, SubMenu};
+            let item = |label: &str, action: Action| -> ksni::MenuItem<Self> {
+                StandardItem {
+                    label: label.to_string(),
+                    activate: Box::new(move |tray: &mut Self| (tray.on_action)(action.clone())),
+                    ..Default::default()
+                }
+                .into()
+            };
+            let check = |label: &str, checked: bool, action: Action| -> ksni::MenuItem<Self> {
+                CheckmarkItem {
+                    label: label.to_string(),
+                    checked,
+                    activate: Box::new(move |tray: &mut Self| (tray.on_action)(action.clone())),
+                    ..Default::default()
+                }
+                .into()
+            };
+            let menu = &self.menu;
+            let games = menu
+                .games
+                .iter()
+                .map(|game| {
+                    check(
+                        &game.name,
+                        game.appid == menu.appid,
+                        Action::SwitchGame(game.appid),
+                    )
+                })
+                .collect();
+            vec![
+                item(&menu.open, Action::Open),
+                ksni::MenuItem::Separator,
+                check(&menu.popup, menu.popup_on, Action::TogglePopup),
+                check(&menu.mute, menu.muted, Action::ToggleMute),
+                SubMenu {
+                    label: menu.switch_game.clone(),
+                    enabled: menu.games.len() > 1,
+                    submenu: games,
+                    ..Default::default()
+                }
+                .into(),
+                ksni::MenuItem::Separator,
+                item(&menu.quit, Action::Quit),
+            ]
+        }
+    }
+
+    pub struct Handle(ksni::blocking::Handle<SniTray>);
+
+    impl Handle {
+        pub fn update(&self, menu: &Menu) {
+            self.0.update(|tray| tray.menu = menu.clone());
+        }
+    }
+
+    pub fn spawn(menu: &Menu, on_action: Callback) -> Result<Handle, String> {
+        let tray = SniTray {
+            menu: menu.clone(),
+            on_action,
+        };
+        tray.spawn().map(Handle).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use tray_icon::{
+        menu::{self, CheckMenuItem, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+        TrayIcon, TrayIconBuilder, TrayIconEvent,
+    };
+
+    use super::{Action, Callback, Menu};
+
+    pub struct Handle(TrayIcon);
+
+    impl Handle {
+        pub fn update(&self, menu: &Menu) {
+            match build(menu) {
+                Ok(built) => self.0.set_menu(Some(Box::new(built))),
+                Err(e) => println!("Can not update the tray menu: {}", e),
+            }
+            let _ = self.0.set_tooltip(Some(&menu.title));
+        }
+    }
+
+    fn build(menu: &Menu) -> Result<menu::Menu, menu::Error> {
+        let games = Submenu::new(&menu.switch_game, menu.games.len() > 1);
+        for game in &menu.games {
+            games.append(&CheckMenuItem::with_id(
+                Action::SwitchGame(game.appid).id(),
+                &game.name,
+                true,
+                game.appid == menu.appid,
+                None,
+            ))?;
+        }
+        menu::Menu::with_items(&[
+            &MenuItem::with_id(Action::Open.id(), &menu.open, true, None),
+            &PredefinedMenuItem::separator(),
+            &CheckMenuItem::with_id(
+                Action::TogglePopup.id(),
+                &menu.popup,
+                true,
+                menu.popup_on,
+                None,
+            ),
+            &CheckMenuItem::with_id(Action::ToggleMute.id(), &menu.mute, true, menu.muted, None),
+            &games,
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(Action::Quit.id(), &menu.quit, true, None),
+        ])
+    }
+
+    /// must be called on the thread of the event loop
+    pub fn spawn(menu: &Menu, on_action: Callback) -> Result<Handle, String> {
+        let icon =
+            tray_icon::Icon::from_rgba(super::icon_rgba(), super::ICON_SIZE, super::ICON_SIZE)
+                .map_err(|e| e.to_string())?;
+        let tray = TrayIconBuilder::new()
+            .with_icon(icon)
+            .with_tooltip(&menu.title)
+            .with_menu(Box::new(build(menu).map_err(|e| e.to_string())?))
+            .build()
+            .map_err(|e| e.to_string())?;
+        let on_menu = on_action.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some(action) = Action::from_id(event.id.as_ref()) {
+                on_menu(action);
+            }
+        }));
+        TrayIconEvent::set_event_handler(Some(move |event| {
+            if let TrayIconEvent::DoubleClick { .. } = event {
+                on_action(Action::Open);
+            }
+        }));
+        Ok(Handle(tray))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod platform {
+    use super::{Callback, Menu};
+
+    pub struct Handle;
+
+    impl Handle {
+        pub fn update(&self, _menu: &Menu) {}
+    }
+
+    pub fn spawn(_menu: &Menu, _on_action: Callback) -> Result<Handle, String> {
+        Err("no tray support on this system".to_string())
+    }
+}
+
+#[test]
+fn tray_menu_ids() {
+    for action in [
+        Action::Open,
+        Action::TogglePopup,
+        Action::ToggleMute,
+        Action::SwitchGame(480),
+        Action::Quit,
+    ] {
+        assert_eq!(Action::from_id(&action.id()), Some(action));
+    }
+    assert_eq!(Action::from_id("game:x"), None);
+    assert_eq!(icon_rgba().len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+}
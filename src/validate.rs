@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fmt};
+
+use crate::achievement::{Achievements, AchievementsRaw};
+
+/// A mistake in steam_settings/achievements.json, or between it and the save file
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// more than one achievement has the name
+    DuplicateName { id: String, count: usize },
+    /// no title in a display language used by the schema
+    MissingDisplayName { id: String, language: String },
+    /// no description in a display language used by the schema
+    MissingDescription { id: String, language: String },
+    /// hidden is not "0" or "1"
+    InvalidHidden { id: String, value: String },
+    /// "icon" or "icon_gray" is empty
+    EmptyIcon { id: String, field: &'static str },
+    /// in the save file but not in the schema, so it is never shown
+    UnknownSaved { id: String },
+    /// the schema file can not be read or parsed, with the line and column of a JSON error
+    Unreadable {
+        path: String,
+        error: String,
+        position: Option<(usize, usize)>,
+    },
+}
+
+impl Problem {
+    /// the achievement it is about, the file for an unreadable schema
+    pub fn id(&self) -> &str {
+        match self {
+            Problem::DuplicateName { id, .. }
+            | Problem::MissingDisplayName { id, .. }
+            | Problem::MissingDescription { id, .. }
+            | Problem::InvalidHidden { id, .. }
+            | Problem::EmptyIcon { id, .. }
+            | Problem::UnknownSaved { id } => id,
+            Problem::Unreadable { path, .. } => path,
+        }
+    }
+
+    /// errors break the reminder, the others only make it fall back to something:
+    /// the earned icon is looked up by the name, the locked one is made gray,
+    /// and texts come from the next language
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Problem::DuplicateName { .. }
+                | Problem::InvalidHidden { .. }
+                | Problem::UnknownSaved { .. }
+                | Problem::Unreadable { .. }
+        )
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::DuplicateName { id, count } => {
+                write!(f, "{} is the name of {} achievements", id, count)
+            }
+            Problem::MissingDisplayName { id, language } => {
+                write!(f, "{} has no displayName in {}", id, language)
+            }
+            Problem::MissingDescription { id, language } => {
+                write!(f, "{} has no description in {}", id, language)
+            }
+            Problem::InvalidHidden { id, value } => {
+                write!(f, "{} has hidden {:?}, expected \"0\" or \"1\"", id, value)
+            }
+            Problem::EmptyIcon { id, field } => write!(f, "{} has an empty {}", id, field),
+            Problem::UnknownSaved { id } => {
+                write!(f, "{} is in the save file but not in the schema", id)
+            }
+            Problem::Unreadable {
+                path,
+                error,
+                position,
+            } => {
+                write!(f, "{} can not be read: {}", path, error)?;
+                match position {
+                    Some((line, column)) => write!(f, " at line {} column {}", line, column),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// every problem of the schema, in schema order, then the unknown saved achievements by name.
+///
+/// Languages are checked among the display languages the schema has at least one text in.
+pub fn validate(raw: &AchievementsRaw, saved: &Achievements) -> Vec<Problem> {
+    let mut problems = vec![];
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for achievement in &raw.achievements {
+        *counts.entry(&achievement.name).or_default() += 1;
+    }
+    let schema_languages = raw.get_schema_languages();
+    let languages: Vec<&String> = raw
+        .languages
+        .iter()
+        .filter(|l| schema_languages.contains(&l.as_str()))
+        .collect();
+    for (i, achievement) in raw.achievements.iter().enumerate() {
+        let id = &achievement.name;
+        let count = counts[id.as_str()];
+        let first = raw.achievements.iter().position(|a| a.name == *id) == Some(i);
+        if count > 1 && first {
+            problems.push(Problem::DuplicateName {
+                id: id.clone(),
+                count,
+            });
+        }
+        for language in &languages {
+            if !achievement.displayName.contains_key(*language) {
+                problems.push(Problem::MissingDisplayName {
+                    id: id.clone(),
+                    language: language.to_string(),
+                });
+            }
+            if !achievement.description.contains_key(*language) {
+                problems.push(Problem::MissingDescription {
+                    id: id.clone(),
+                    language: language.to_string(),
+                });
+            }
+        }
+        if achievement.hidden != "0" && achievement.hidden != "1" {
+            problems.push(Problem::InvalidHidden {
+                id: id.clone(),
+                value: achievement.hidden.clone(),
+            });
+        }
+        for (field, file) in [
+            ("icon", &achievement.icon),
+            ("icon_gray", &achievement.icon_gray),
+        ] {
+            if file.trim().is_empty() {
+                problems.push(Problem::EmptyIcon {
+                    id: id.clone(),
+                    field,
+                });
+            }
+        }
+    }
+    let mut unknown: Vec<&String> = saved
+        .achievements
+        .keys()
+        .filter(|name| !counts.contains_key(name.as_str()))
+        .collect();
+    unknown.sort();
+    problems.extend(
+        unknown
+            .into_iter()
+            .map(|id| Problem::UnknownSaved { id: id.clone() }),
+    );
+    problems
+}
+
+#[test]
+fn validate_schema() {
+    use crate::achievement::{Achievement, AchievementRaw};
+    let achievement = |name: &str, hidden: &str| AchievementRaw {
+        name: name.to_string(),
+        hidden: hidden.to_string(),
+        icon: format!("{}.jpg", name),
+        icon_gray: format!("{}_gray.jpg", name),
+        displayName: [("english".to_string(), name.to_string())].into(),
+        description: [("english".to_string(), name.to_string())].into(),
+    };
+    let mut translated = achievement("ACH_B", "1");
+    translated
+        .displayName
+        .insert("schinese".to_string(), "乙".to_string());
+    translated.icon_gray.clear();
+    let raw = AchievementsRaw {
+        achievements: vec![
+            achievement("ACH_A", "0"),
+            translated,
+            achievement("ACH_A", "true"),
+        ],
+        languages: vec!["schinese".to_string(), "english".to_string()],
+        ..Default::default()
+    };
+    let saved = Achievements {
+        achievements: [
+            ("ACH_A".to_string(), Achievement::default()),
+            ("ACH_OLD".to_string(), Achievement::default()),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let problems = validate(&raw, &saved);
+    let found: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+    assert_eq!(
+        found,
+        [
+            "ACH_A is the name of 2 achievements",
+            "ACH_A has no displayName in schinese",
+            "ACH_A has no description in schinese",
+            "ACH_B has no description in schinese",
+            "ACH_B has an empty icon_gray",
+            "ACH_A has no displayName in schinese",
+            "ACH_A has no description in schinese",
+            "ACH_A has hidden \"true\", expected \"0\" or \"1\"",
+            "ACH_OLD is in the save file but not in the schema",
+        ]
+    );
+    assert_eq!(problems.iter().filter(|p| p.is_error()).count(), 3);
+}
+
+#[test]
+fn goldberg_hidden_is_kept() {
+    let goldberg = r#"[{"name":"ACH_WIN","hidden":"true","displayName":{"english":"Winner"},
+        "description":{"english":"Win"},"icon":"a.jpg","icon_gray":"b.jpg"}]"#;
+    let raw = AchievementsRaw {
+        achievements: crate::schema::parse(goldberg, "english").unwrap(),
+        languages: vec!["english".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        validate(&raw, &Achievements::default()),
+        [Problem::InvalidHidden {
+            id: "ACH_WIN".to_string(),
+            value: "true".to_string(),
+        }]
+    );
+    // the Web API has numbers, turned into "0" or "1"
+    let api = r#"{"game":{"availableGameStats":{"achievements":[
+        {"name":"ACH_WIN","hidden":1,"displayName":"Winner","description":"Win",
+         "icon":"a.jpg","icongray":"b.jpg"}]}}}"#;
+    let raw = AchievementsRaw {
+        achievements: crate::schema::parse(api, "english").unwrap(),
+        ..raw
+    };
+    assert!(validate(&raw, &Achievements::default()).is_empty());
+}
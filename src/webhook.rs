@@ -0,0 +1,388 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{achievement::AchievementEvent, notifier::Notifier, setting::Setting};
+
+/// attempts of a new delivery before it is left in the queue
+const ATTEMPTS: u32 = 3;
+/// delay before the second attempt, doubled for each following one
+const BACKOFF: Duration = Duration::from_secs(1);
+/// how often deliveries left in the queue are tried again, once each
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// deliveries kept in the queue, the oldest ones are dropped beyond
+const MAX_QUEUE: usize = 100;
+const QUEUE_NAME: &str = "webhook_queue.json";
+
+/// Body sent to the webhook
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// the plain payload, see `WebhookNotifier::payload`
+    #[default]
+    Json,
+    /// a Discord message with an embed
+    Discord,
+}
+
+/// A webhook in the setting, like {"url": "https://...", "format": "discord"}
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+/// A POST waiting to be delivered
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+struct Delivery {
+    url: String,
+    body: String,
+}
+
+/// What became of a delivery
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Delivered,
+    /// refused by the webhook, like 400 or 404, sending it again would not help
+    Rejected,
+    /// to be sent again later
+    Failed,
+}
+
+/// Deliveries that failed, kept in `{data dir}/webhook_queue.json` across restarts
+struct Queue {
+    path: PathBuf,
+    deliveries: Vec<Delivery>,
+}
+
+impl Queue {
+    fn load(path: PathBuf) -> Self {
+        let deliveries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { path, deliveries }
+    }
+
+    fn push(&mut self, delivery: Delivery) {
+        self.deliveries.push(delivery);
+        if self.deliveries.len() > MAX_QUEUE {
+            let dropped = self.deliveries.len() - MAX_QUEUE;
+            println!("Webhook queue is full, drop the {} oldest", dropped);
+            self.deliveries.drain(..dropped);
+        }
+        self.save();
+    }
+
+    /// try every queued delivery once, keeping the failed ones
+    fn retry(&mut self) {
+        if self.deliveries.is_empty() {
+            return;
+        }
+        self.deliveries
+            .retain(|delivery| WebhookNotifier::deliver(delivery, 1) == Outcome::Failed);
+        self.save();
+    }
+
+    fn save(&self) {
+        if self.deliveries.is_empty() {
+            let _ = fs::remove_file(&self.path);
+            return;
+        }
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let written = fs::write(
+            &self.path,
+            serde_json::to_string_pretty(&self.deliveries).unwrap(),
+        );
+        if let Err(e) = written {
+            println!("Can not save webhook queue {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// POST the achievement events to the webhooks of the setting.
+///
+/// Deliveries are retried with backoff on a worker thread; those still failing
+/// are kept in `{data dir}/webhook_queue.json` and sent again later, even after a restart.
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    webhooks: Vec<Webhook>,
+    game: String,
+    appid: u32,
+    sender: mpsc::Sender<Delivery>,
+}
+
+impl WebhookNotifier {
+    /// None if there is no webhook in the setting
+    pub fn new(setting: &Setting) -> Option<Self> {
+        let webhooks = setting.get_webhooks();
+        if webhooks.is_empty() {
+            return None;
+        }
+        let queue = PathBuf::from(setting.get_data_dir()).join(QUEUE_NAME);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || Self::deliver_loop(receiver, queue));
+        Some(Self {
+            webhooks,
+            game: setting.get_game_name(),
+            appid: setting.get_app_id(),
+            sender,
+        })
+    }
+
+    /// new deliveries are tried at once, the queued ones every RETRY_INTERVAL
+    fn deliver_loop(receiver: mpsc::Receiver<Delivery>, queue_path: PathBuf) {
+        let mut queue = Queue::load(queue_path);
+        queue.retry();
+        let mut last_retry = Instant::now();
+        loop {
+            let wait = RETRY_INTERVAL.saturating_sub(last_retry.elapsed());
+            match receiver.recv_timeout(wait) {
+                Ok(delivery) => {
+                    // take whatever else arrived meanwhile
+                    for delivery in
+                        std::iter::once(delivery).chain(receiver.try_iter().collect::<Vec<_>>())
+                    {
+                        if Self::deliver(&delivery, ATTEMPTS) == Outcome::Failed {
+                            queue.push(delivery);
+                        }
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    queue.retry();
+                    last_retry = Instant::now();
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    /// try a delivery up to `attempts` times, with backoff
+    fn deliver(delivery: &Delivery, attempts: u32) -> Outcome {
+        let mut delay = BACKOFF;
+        for attempt in 1..=attempts {
+            match post(&delivery.url, &delivery.body) {
+                Ok(()) => return Outcome::Delivered,
+                Err(e) => {
+                    println!(
+                        "Webhook {} failed ({}/{}): {}",
+                        delivery.url, attempt, attempts, e
+                    );
+                    // too many requests is worth another try
+                    if matches!(*e, ureq::Error::Status(400..=499, _))
+                        && !matches!(*e, ureq::Error::Status(429, _))
+                    {
+                        return Outcome::Rejected;
+                    }
+                }
+            }
+            if attempt < attempts {
+                thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+        Outcome::Failed
+    }
+
+    /// Plain payload:
+    /// `{event, game, appid, achievement, title, description, time, earned, total, completion}`
+    /// with time in RFC 3339 and completion in percent
+    pub fn payload(&self, event: &AchievementEvent) -> serde_json::Value {
+        let ac = &event.achievement;
+        serde_json::json!({
+            "event": event.kind.name(),
+            "game": self.game,
+            "appid": self.appid,
+            "achievement": ac.id,
+            "title": ac.title,
+            "description": ac.description,
+            "time": rfc3339(ac.earned_time),
+            "earned": event.earned,
+            "total": event.total,
+            "completion": completion(event),
+        })
+    }
+
+    /// Discord message with one embed
+    pub fn discord_payload(&self, event: &AchievementEvent, headline: &str) -> serde_json::Value {
+        let ac = &event.achievement;
+        serde_json::json!({
+            "username": "Steam Achievements Reminder",
+            "embeds": [{
+                "author": { "name": headline },
+                "title": ac.title,
+                "description": ac.description,
+                "color": if ac.state { 0x2e7d32 } else { 0xef6c00 },
+                "timestamp": rfc3339(ac.earned_time),
+                "footer": {
+                    "text": format!(
+                        "{} · {}/{} ({:.1}%)",
+                        self.game, event.earned, event.total, completion(event)
+                    )
+                },
+            }],
+        })
+    }
+
+    /// queue the event for every webhook
+    fn send(&self, event: &AchievementEvent, headline: &str) {
+        for webhook in &self.webhooks {
+            let body = match webhook.format {
+                WebhookFormat::Json => self.payload(event),
+                WebhookFormat::Discord => self.discord_payload(event, headline),
+            };
+            let delivery = Delivery {
+                url: webhook.url.clone(),
+                body: body.to_string(),
+            };
+            self.sender.send(delivery).unwrap();
+        }
+    }
+
+    /// send a made up unlock to every webhook
+    pub fn send_test(&self, headline: &str) {
+        let mut event = AchievementEvent::default();
+        event.achievement.id = "TEST_ACHIEVEMENT".to_string();
+        event.achievement.title = "Test achievement".to_string();
+        event.achievement.description = "Sent by the reminder to test the webhook".to_string();
+        event.achievement.state = true;
+        event.achievement.earned_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        event.earned = 1;
+        event.total = 1;
+        self.send(&event, headline);
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn notify(&self, event: &AchievementEvent, headline: &str) -> Result<(), String> {
+        self.send(event, headline);
+        Ok(())
+    }
+}
+
+fn completion(event: &AchievementEvent) -> f64 {
+    if event.total == 0 {
+        0.0
+    } else {
+        event.earned as f64 * 100.0 / event.total as f64
+    }
+}
+
+fn rfc3339(time: u64) -> String {
+    chrono::DateTime::from_timestamp(time as i64, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+fn post(url: &str, body: &str) -> Result<(), Box<ureq::Error>> {
+    ureq::post(url)
+        .timeout(Duration::from_secs(10))
+        .set("Content-Type", "application/json")
+        .send_string(body)
+        .map(|_| ())
+        .map_err(Box::new)
+}
+
+#[test]
+fn post_to_local_server() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+                length = v.trim().parse().unwrap();
+            }
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8(body).unwrap()
+    });
+    let (sender, _) = mpsc::channel();
+    let notifier = WebhookNotifier {
+        webhooks: vec![],
+        game: "Game".to_string(),
+        appid: 480,
+        sender,
+    };
+    let mut event = AchievementEvent::default();
+    event.achievement.id = "ACH_WIN".to_string();
+    event.earned = 1;
+    event.total = 4;
+    let delivery = Delivery {
+        url,
+        body: notifier.payload(&event).to_string(),
+    };
+    assert_eq!(WebhookNotifier::deliver(&delivery, 1), Outcome::Delivered);
+    let body: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+    assert_eq!(body["achievement"], "ACH_WIN");
+    assert_eq!(body["appid"], 480);
+    assert_eq!(body["completion"], 25.0);
+}
+
+#[test]
+fn queue_while_offline() {
+    let dir = crate::temp_dir::TempDir::new("webhook");
+    let path = dir.join(QUEUE_NAME);
+    // a port nothing listens on for now
+    let address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let delivery = |path: &str| Delivery {
+        url: format!("http://{}/{}", address, path),
+        body: "{}".to_string(),
+    };
+    let mut queue = Queue::load(path.clone());
+    queue.push(delivery("hook"));
+    queue.push(delivery("gone"));
+    queue.retry();
+    assert_eq!(queue.deliveries.len(), 2);
+
+    let server = tiny_http::Server::http(address).unwrap();
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let code = if request.url() == "/hook" { 204 } else { 404 };
+            let _ = request.respond(tiny_http::Response::empty(code));
+        }
+    });
+    let mut queue = Queue::load(path.clone());
+    assert_eq!(queue.deliveries, [delivery("hook"), delivery("gone")]);
+    queue.retry();
+    assert!(queue.deliveries.is_empty());
+    assert!(!path.exists());
+
+    for _ in 0..MAX_QUEUE + 5 {
+        queue.deliveries.push(delivery("hook"));
+    }
+    queue.push(delivery("last"));
+    assert_eq!(queue.deliveries.len(), MAX_QUEUE);
+    assert_eq!(queue.deliveries.last(), Some(&delivery("last")));
+}